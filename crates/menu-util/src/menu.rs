@@ -9,6 +9,9 @@ use serde::Deserialize;
 use serde_json::Value;
 use std::collections::HashMap;
 
+/// Label of the dropdown menu window.
+pub const MENU_WINDOW_LABEL: &str = "macos";
+
 /// Represents the position of a monitor.
 #[derive(Deserialize)]
 pub struct MonitorPosition {
@@ -27,7 +30,7 @@ pub struct ButtonPosition {
 
 /// Builds a hidden, non-interactive menu window for the app.
 fn build_menu_window(app_handle: &AppHandle) -> tauri::Result<WebviewWindow> {
-    WebviewWindowBuilder::new(app_handle, "macos", WebviewUrl::App("/".into()))
+    WebviewWindowBuilder::new(app_handle, MENU_WINDOW_LABEL, WebviewUrl::App("/".into()))
         .title("Dropdown - EdgeBar")
         .focused(false)
         .visible(false)
@@ -45,7 +48,7 @@ fn build_menu_window(app_handle: &AppHandle) -> tauri::Result<WebviewWindow> {
 ///
 /// Spawns a background task to create the window and set its style.
 pub fn initialize_menu_window(app_handle: &AppHandle) -> anyhow::Result<()> {
-    if app_handle.get_webview_window("macos").is_none() {
+    if app_handle.get_webview_window(MENU_WINDOW_LABEL).is_none() {
         let app_handle_clone = app_handle.clone();
         task::spawn(async move {
             match build_menu_window(&app_handle_clone) {
@@ -84,7 +87,7 @@ pub fn show_menu(
     button: ButtonPosition,
     monitor: MonitorPosition,
 ) -> anyhow::Result<()> {
-    if let Some(window) = app_handle.get_webview_window("macos") {
+    if let Some(window) = app_handle.get_webview_window(MENU_WINDOW_LABEL) {
         window.hide()?;
 
         let scale = window.scale_factor().unwrap_or(1.0);
@@ -114,7 +117,7 @@ pub fn resize_menu(
     logical_width: f64,
     logical_height: f64,
 ) -> anyhow::Result<()> {
-    if let Some(window) = app_handle.get_webview_window("macos") {
+    if let Some(window) = app_handle.get_webview_window(MENU_WINDOW_LABEL) {
         window.hide()?;
 
         let scale = window.scale_factor().unwrap_or(1.0);
@@ -132,7 +135,7 @@ pub fn resize_menu(
 
 /// Hides the menu window if it exists.
 pub fn hide_menu(app_handle: &AppHandle) -> anyhow::Result<()> {
-    if let Some(window) = app_handle.get_webview_window("macos") {
+    if let Some(window) = app_handle.get_webview_window(MENU_WINDOW_LABEL) {
         window.hide()?;
     }
     Ok(())
@@ -36,6 +36,14 @@ impl Encoding {
       Err(_) => Buffer::Raw(line),
     }
   }
+
+  /// Creates a streaming decoder for output that is read in arbitrary
+  /// chunks. Returns `None` for raw output.
+  pub fn new_decoder(&self) -> Option<encoding_rs::Decoder> {
+    <&Encoding as TryInto<&'static encoding_rs::Encoding>>::try_into(self)
+      .ok()
+      .map(|encoding| encoding.new_decoder_with_bom_removal())
+  }
 }
 
 impl TryInto<&'static encoding_rs::Encoding> for &Encoding {
@@ -7,6 +7,15 @@ pub enum Error {
 
   #[error("Invalid buffer")]
   InvalidBuffer,
+
+  #[error("Pseudo-terminal error: {0}")]
+  Pty(String),
+
+  #[error("Process was not spawned with a pseudo-terminal")]
+  PtyRequired,
+
+  #[error("Command was cancelled")]
+  Cancelled,
 }
 
 impl Serialize for Error {
@@ -16,6 +16,30 @@ pub struct CommandOptions {
 
   /// Character encoding for stdout/stderr.
   pub encoding: Encoding,
+
+  /// Attach the process to a pseudo-terminal of the given size instead
+  /// of plain pipes. Stdout and stderr are merged and streamed as they
+  /// are written, without line buffering.
+  pub pty: Option<PtyOptions>,
+
+  /// Time in milliseconds to wait for `Shell::exec` to finish before
+  /// giving up on the process.
+  pub timeout_ms: Option<u64>,
+
+  /// Kill the process when `timeout_ms` is exceeded. Otherwise, the
+  /// process is left running in the background.
+  pub kill_on_timeout: bool,
+
+  /// Maximum number of bytes that `Shell::exec` collects from each of
+  /// stdout and stderr. Any further output is discarded.
+  pub max_output_bytes: Option<usize>,
+}
+
+/// Size of a pseudo-terminal in character cells.
+#[derive(Debug, Clone, Copy, Deserialize, Eq, PartialEq, Serialize)]
+pub struct PtyOptions {
+  pub cols: u16,
+  pub rows: u16,
 }
 
 impl Default for CommandOptions {
@@ -25,6 +49,10 @@ impl Default for CommandOptions {
       env: HashMap::default(),
       clear_env: false,
       encoding: Encoding::Utf8,
+      pty: None,
+      timeout_ms: None,
+      kill_on_timeout: true,
+      max_output_bytes: None,
     }
   }
 }
@@ -1,21 +1,32 @@
+#[cfg(unix)]
+use std::os::unix::process::ExitStatusExt;
+#[cfg(windows)]
 use std::os::windows::process::CommandExt;
 use std::{
   ffi::OsStr,
-  io::Write,
+  future::{pending, Future},
+  io::{Read, Write},
   process::{Command, Stdio},
-  sync::{Arc, RwLock},
+  sync::{Arc, Mutex, RwLock},
   thread::spawn,
+  time::Duration,
 };
 
 use os_pipe::{pipe, PipeWriter};
+use portable_pty::{native_pty_system, ChildKiller, CommandBuilder, MasterPty, PtySize};
 use serde::{Deserialize, Serialize};
 use shared_child::SharedChild;
 use tokio::sync::mpsc;
 
-use crate::{encoding::Encoding, options::CommandOptions, StdoutReader};
+use crate::{
+  encoding::Encoding,
+  options::{CommandOptions, PtyOptions},
+  StdoutReader,
+};
 
 pub type ProcessId = u32;
 
+#[cfg(windows)]
 const CREATE_NO_WINDOW: u32 = 0x08000000;
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
@@ -40,7 +51,7 @@ impl Buffer {
   ///
   /// # Examples
   /// ```
-  /// use crate::shell::Buffer;
+  /// use shell_util::Buffer;
   /// let mut buffer = Buffer::new(false);
   /// buffer.push(Buffer::Text("Hello".to_string())).unwrap();
   /// assert_eq!(buffer, Buffer::Text("Hello".to_string()));
@@ -74,6 +85,65 @@ impl Buffer {
       Buffer::Raw(bytes) => bytes,
     }
   }
+
+  /// Returns the length of the buffer in bytes.
+  pub fn len(&self) -> usize {
+    self.as_bytes().len()
+  }
+
+  /// Returns whether the buffer is empty.
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  /// Shortens the buffer to at most `len` bytes. Text buffers are cut at
+  /// the closest character boundary below `len`.
+  ///
+  /// # Examples
+  /// ```
+  /// use shell_util::Buffer;
+  /// let mut buffer = Buffer::Text("héllo".to_string());
+  /// buffer.truncate(2);
+  /// assert_eq!(buffer, Buffer::Text("h".to_string()));
+  /// ```
+  pub fn truncate(&mut self, len: usize) {
+    match self {
+      Buffer::Text(string) => {
+        let mut len = len.min(string.len());
+
+        while !string.is_char_boundary(len) {
+          len -= 1;
+        }
+
+        string.truncate(len);
+      }
+      Buffer::Raw(bytes) => bytes.truncate(len),
+    }
+  }
+
+  /// Pushes a buffer of the same type into the current buffer, without
+  /// growing past `max_len` bytes.
+  ///
+  /// Returns `true` if any of the incoming buffer was discarded.
+  fn push_limited(&mut self, buffer: Buffer, max_len: Option<usize>) -> crate::Result<bool> {
+    let Some(max_len) = max_len else {
+      self.push(buffer)?;
+      return Ok(false);
+    };
+
+    if self.len() >= max_len {
+      return Ok(!buffer.is_empty());
+    }
+
+    self.push(buffer)?;
+
+    if self.len() > max_len {
+      self.truncate(max_len);
+      return Ok(true);
+    }
+
+    Ok(false)
+  }
 }
 
 /// Event emitted by child process execution.
@@ -100,27 +170,87 @@ pub enum ChildProcessEvent {
 /// The child process spawned by a shell command.
 #[derive(Debug)]
 pub struct ChildProcess {
-  inner: Arc<SharedChild>,
-  stdin_writer: PipeWriter,
+  inner: ChildHandle,
   rx: mpsc::Receiver<ChildProcessEvent>,
 }
 
+/// Handle to a child process, depending on how its stdio is attached.
+#[derive(Debug)]
+enum ChildHandle {
+  Pipe {
+    child: Arc<SharedChild>,
+    stdin_writer: PipeWriter,
+  },
+  Pty(PtyHandle),
+}
+
+/// Handle to a child process attached to a pseudo-terminal.
+struct PtyHandle {
+  pid: ProcessId,
+  killer: Box<dyn ChildKiller + Send + Sync>,
+  writer: Box<dyn Write + Send>,
+
+  /// Controlling side of the pseudo-terminal. Set to `None` once the
+  /// pseudo-terminal is closed.
+  master: Arc<Mutex<Option<Box<dyn MasterPty + Send>>>>,
+}
+
+impl std::fmt::Debug for PtyHandle {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("PtyHandle")
+      .field("pid", &self.pid)
+      .field("killer", &self.killer)
+      .finish_non_exhaustive()
+  }
+}
+
 impl ChildProcess {
-  /// Writes to the child process' stdin.
+  /// Writes to the child process' stdin. For pseudo-terminals, this is
+  /// the input typed into the terminal.
   pub fn write(&mut self, buffer: &[u8]) -> crate::Result<()> {
-    self.stdin_writer.write_all(buffer)?;
+    match &mut self.inner {
+      ChildHandle::Pipe { stdin_writer, .. } => stdin_writer.write_all(buffer)?,
+      ChildHandle::Pty(pty) => {
+        pty.writer.write_all(buffer)?;
+        pty.writer.flush()?;
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Resizes the pseudo-terminal of the child process.
+  ///
+  /// Returns an error if the process was not spawned with a
+  /// pseudo-terminal.
+  pub fn resize(&self, size: PtyOptions) -> crate::Result<()> {
+    let ChildHandle::Pty(pty) = &self.inner else {
+      return Err(crate::Error::PtyRequired);
+    };
+
+    if let Some(master) = pty.master.lock().unwrap().as_ref() {
+      master.resize(size.into()).map_err(pty_error)?;
+    }
+
     Ok(())
   }
 
   /// Sends a kill signal to the child process.
-  pub fn kill(self) -> crate::Result<()> {
-    self.inner.kill()?;
+  pub fn kill(&mut self) -> crate::Result<()> {
+    match &mut self.inner {
+      ChildHandle::Pipe { child, .. } => child.kill()?,
+      ChildHandle::Pty(pty) => pty.killer.kill()?,
+    }
+
     Ok(())
   }
 
   /// Returns the child process' pid.
   pub fn pid(&self) -> u32 {
-    self.inner.id()
+    match &self.inner {
+      ChildHandle::Pipe { child, .. } => child.id(),
+      ChildHandle::Pty(pty) => pty.pid,
+    }
   }
 
   /// Returns a channel of events from the child process.
@@ -129,6 +259,21 @@ impl ChildProcess {
   }
 }
 
+impl From<PtyOptions> for PtySize {
+  fn from(size: PtyOptions) -> Self {
+    PtySize {
+      rows: size.rows,
+      cols: size.cols,
+      ..Default::default()
+    }
+  }
+}
+
+/// Converts an error from the pseudo-terminal backend.
+fn pty_error(err: impl std::fmt::Display) -> crate::Error {
+  crate::Error::Pty(err.to_string())
+}
+
 /// The result of a process after it has terminated.
 #[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
 pub struct ExitStatus {
@@ -138,12 +283,47 @@ pub struct ExitStatus {
   /// Whether the process exited with a zero exit code.
   pub success: bool,
 
-  /// Termination signal if process was killed.
+  /// Termination signal if process was killed. Always `None` on
+  /// Windows and for processes attached to a pseudo-terminal.
   pub signal: Option<i32>,
 }
 
+impl From<std::process::ExitStatus> for ExitStatus {
+  fn from(status: std::process::ExitStatus) -> Self {
+    #[cfg(unix)]
+    let signal = status.signal();
+
+    #[cfg(not(unix))]
+    let signal = None;
+
+    Self {
+      code: status.code(),
+      success: status.success(),
+      signal,
+    }
+  }
+}
+
+impl From<portable_pty::ExitStatus> for ExitStatus {
+  fn from(status: portable_pty::ExitStatus) -> Self {
+    // The signal is only available by name, so the exit code is left
+    // empty for signalled processes instead.
+    let code = match status.signal() {
+      Some(_) => None,
+      None => Some(status.exit_code() as i32),
+    };
+
+    Self {
+      code,
+      success: status.success(),
+      signal: None,
+    }
+  }
+}
+
 /// The output of a finished process.
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct ShellExecOutput {
   /// The exit code and termination signal of the process.
   #[serde(flatten)]
@@ -154,6 +334,14 @@ pub struct ShellExecOutput {
 
   /// The buffer that the process wrote to stderr.
   pub stderr: Buffer,
+
+  /// Whether the process did not finish within the configured timeout.
+  /// The exit status is empty in that case.
+  pub timed_out: bool,
+
+  /// Whether stdout or stderr exceeded the configured output limit and
+  /// was cut short.
+  pub truncated: bool,
 }
 
 /// The type to spawn commands.
@@ -168,12 +356,14 @@ impl Shell {
   ///
   /// ```rust,no_run
   /// use shell_util::{CommandOptions, Shell};
+  /// # async fn example() {
   /// let output =
   ///     Shell::exec("echo", &["Hello!"], &CommandOptions::default())
   ///       .await
   ///       .unwrap();
   /// assert!(output.status.success);
   /// assert_eq!(output.stdout.as_str().unwrap(), "Hello!");
+  /// # }
   /// ```
   pub async fn exec<I, S>(
     program: &str,
@@ -183,25 +373,93 @@ impl Shell {
   where
     I: IntoIterator<Item = S>,
     S: AsRef<OsStr>,
+  {
+    Self::exec_with_cancel(program, args, options, pending()).await
+  }
+
+  /// Executes a command as a child process like `Shell::exec`, but kills
+  /// the process and returns `Error::Cancelled` if `cancel` completes
+  /// before the process has finished.
+  ///
+  /// # Examples
+  ///
+  /// ```rust,no_run
+  /// use shell_util::{CommandOptions, Shell};
+  /// # async fn example() {
+  /// let (cancel_tx, cancel_rx) = tokio::sync::oneshot::channel::<()>();
+  /// cancel_tx.send(()).unwrap();
+  ///
+  /// let output = Shell::exec_with_cancel(
+  ///   "sleep",
+  ///   &["10"],
+  ///   &CommandOptions::default(),
+  ///   async move {
+  ///     let _ = cancel_rx.await;
+  ///   },
+  /// )
+  /// .await;
+  ///
+  /// assert!(output.is_err());
+  /// # }
+  /// ```
+  pub async fn exec_with_cancel<I, S, F>(
+    program: &str,
+    args: I,
+    options: &CommandOptions,
+    cancel: F,
+  ) -> crate::Result<ShellExecOutput>
+  where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+    F: Future<Output = ()>,
   {
     let mut child = Self::spawn(program, args, options)?;
 
     let mut status = ExitStatus::default();
     let mut stdout = Buffer::new(options.encoding == Encoding::Raw);
     let mut stderr = Buffer::new(options.encoding == Encoding::Raw);
+    let mut timed_out = false;
+    let mut truncated = false;
 
-    while let Some(event) = child.events().recv().await {
-      match event {
-        ChildProcessEvent::Terminated(exit_status) => {
-          status = exit_status;
-        }
-        ChildProcessEvent::Stdout(line) => {
-          stdout.push(line)?;
+    let timeout = async {
+      match options.timeout_ms {
+        Some(timeout_ms) => tokio::time::sleep(Duration::from_millis(timeout_ms)).await,
+        None => pending().await,
+      }
+    };
+
+    tokio::pin!(timeout);
+    tokio::pin!(cancel);
+
+    loop {
+      tokio::select! {
+        event = child.events().recv() => match event {
+          Some(ChildProcessEvent::Terminated(exit_status)) => {
+            status = exit_status;
+          }
+          Some(ChildProcessEvent::Stdout(line)) => {
+            truncated |= stdout.push_limited(line, options.max_output_bytes)?;
+          }
+          Some(ChildProcessEvent::Stderr(line)) => {
+            truncated |= stderr.push_limited(line, options.max_output_bytes)?;
+          }
+          Some(ChildProcessEvent::Error(_)) => {}
+          None => break,
+        },
+        _ = &mut timeout => {
+          // Stop waiting right away, since descendants of the process
+          // can keep its output pipes open after it has been killed.
+          if options.kill_on_timeout {
+            let _ = child.kill();
+          }
+
+          timed_out = true;
+          break;
         }
-        ChildProcessEvent::Stderr(line) => {
-          stderr.push(line)?;
+        _ = &mut cancel => {
+          let _ = child.kill();
+          return Err(crate::Error::Cancelled);
         }
-        ChildProcessEvent::Error(_) => {}
       }
     }
 
@@ -209,6 +467,8 @@ impl Shell {
       status,
       stdout,
       stderr,
+      timed_out,
+      truncated,
     })
   }
 
@@ -218,11 +478,13 @@ impl Shell {
   /// # Examples
   /// ```rust,no_run
   /// use shell_util::{CommandOptions, Shell};
+  /// # async fn example() {
   /// let status =
-  ///     Shell::status("echo", ["Hello!"], CommandOptions::default())
+  ///     Shell.status("echo", ["Hello!"], &CommandOptions::default())
   ///       .await
   ///       .unwrap();
   /// assert!(status.success);
+  /// # }
   /// ```
   pub async fn status<I, S>(
     &self,
@@ -250,15 +512,17 @@ impl Shell {
   /// # Examples
   ///
   /// ```rust,no_run
-  /// use shell_util::{CommandEvent, Shell};
-  /// let child = Shell::spawn("yes", [], CommandOptions::default())
+  /// use shell_util::{ChildProcessEvent, CommandOptions, Shell};
+  /// # async fn example() {
+  /// let mut child = Shell::spawn("yes", [] as [&str; 0], &CommandOptions::default())
   ///   .expect("Failed to spawn yes.");
   ///
   /// while let Some(event) = child.events().recv().await {
-  ///   if let CommandEvent::Stdout(buffer) = event {
+  ///   if let ChildProcessEvent::Stdout(buffer) = event {
   ///     println!("stdout: {}", buffer.as_str().unwrap());
   ///   }
   /// }
+  /// # }
   /// ```
   pub fn spawn<I, S>(
     program: &str,
@@ -269,6 +533,10 @@ impl Shell {
     I: IntoIterator<Item = S>,
     S: AsRef<OsStr>,
   {
+    if let Some(size) = options.pty {
+      return Self::spawn_pty_child(program, args, options, size);
+    }
+
     let mut command = Self::create_command(program, args, options);
     Self::spawn_child(&mut command, options)
   }
@@ -311,11 +579,7 @@ impl Shell {
       let _lock = guard.write().unwrap();
 
       let event = match status {
-        Ok(status) => ChildProcessEvent::Terminated(ExitStatus {
-          code: status.code(),
-          success: status.code().is_some_and(|code| code == 0),
-          signal: None,
-        }),
+        Ok(status) => ChildProcessEvent::Terminated(status.into()),
         Err(err) => ChildProcessEvent::Error(err.to_string()),
       };
 
@@ -323,8 +587,90 @@ impl Shell {
     });
 
     Ok(ChildProcess {
-      inner: child,
-      stdin_writer,
+      inner: ChildHandle::Pipe {
+        child,
+        stdin_writer,
+      },
+      rx,
+    })
+  }
+
+  /// Spawns the command as a child process attached to a new
+  /// pseudo-terminal.
+  fn spawn_pty_child<I, S>(
+    program: &str,
+    args: I,
+    options: &CommandOptions,
+    size: PtyOptions,
+  ) -> crate::Result<ChildProcess>
+  where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+  {
+    let pair = native_pty_system()
+      .openpty(size.into())
+      .map_err(pty_error)?;
+
+    let mut command = CommandBuilder::new(program);
+    command.args(args);
+
+    if let Some(cwd) = &options.cwd {
+      command.cwd(cwd);
+    }
+
+    if options.clear_env {
+      command.env_clear();
+    }
+
+    for (key, value) in &options.env {
+      command.env(key, value);
+    }
+
+    let mut child = pair.slave.spawn_command(command).map_err(pty_error)?;
+
+    // Close the slave side so that reads from the master end once the
+    // child process has exited.
+    drop(pair.slave);
+
+    let pid = child.process_id().unwrap_or_default();
+    let killer = child.clone_killer();
+    let reader = pair.master.try_clone_reader().map_err(pty_error)?;
+    let writer = pair.master.take_writer().map_err(pty_error)?;
+    let master = Arc::new(Mutex::new(Some(pair.master)));
+    let master_ = master.clone();
+    let guard = Arc::new(RwLock::new(()));
+
+    let (tx, rx) = mpsc::channel(1);
+
+    Self::spawn_pty_reader(tx.clone(), guard.clone(), reader, options.encoding.clone());
+
+    spawn(move || {
+      let status = child.wait();
+
+      // The reader of a ConPTY only ends once the pseudo-console is
+      // closed, whereas closing the master on Unix discards unread
+      // output.
+      #[cfg(windows)]
+      master_.lock().unwrap().take();
+
+      let _lock = guard.write().unwrap();
+      master_.lock().unwrap().take();
+
+      let event = match status {
+        Ok(status) => ChildProcessEvent::Terminated(status.into()),
+        Err(err) => ChildProcessEvent::Error(err.to_string()),
+      };
+
+      let _ = tx.blocking_send(event);
+    });
+
+    Ok(ChildProcess {
+      inner: ChildHandle::Pty(PtyHandle {
+        pid,
+        killer,
+        writer,
+        master,
+      }),
       rx,
     })
   }
@@ -350,6 +696,9 @@ impl Shell {
     command.stderr(Stdio::piped());
     command.args(args);
     command.envs(&options.env);
+
+    // Avoid opening a console window for the process.
+    #[cfg(windows)]
     command.creation_flags(CREATE_NO_WINDOW);
 
     command
@@ -377,6 +726,52 @@ impl Shell {
       }
     });
   }
+
+  /// Spawns a thread to read from a pseudo-terminal and emit the output
+  /// through a channel as soon as it is available.
+  fn spawn_pty_reader(
+    tx: mpsc::Sender<ChildProcessEvent>,
+    guard: Arc<RwLock<()>>,
+    mut reader: Box<dyn Read + Send>,
+    encoding: Encoding,
+  ) {
+    spawn(move || {
+      let _lock = guard.read().unwrap();
+      let mut decoder = encoding.new_decoder();
+      let mut chunk = [0; 4096];
+
+      loop {
+        let len = match reader.read(&mut chunk) {
+          Ok(0) => break,
+          Ok(len) => len,
+          Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+          // Reading from the master fails with `EIO` on Linux once the
+          // child process has exited.
+          Err(_) => break,
+        };
+
+        let buffer = match &mut decoder {
+          Some(decoder) => {
+            let mut text =
+              String::with_capacity(decoder.max_utf8_buffer_length(len).unwrap_or(len * 3));
+
+            let _ = decoder.decode_to_string(&chunk[..len], &mut text, false);
+
+            if text.is_empty() {
+              continue;
+            }
+
+            Buffer::Text(text)
+          }
+          None => Buffer::Raw(chunk[..len].to_vec()),
+        };
+
+        if tx.blocking_send(ChildProcessEvent::Stdout(buffer)).is_err() {
+          break;
+        }
+      }
+    });
+  }
 }
 
 #[cfg(test)]
@@ -444,4 +839,96 @@ mod tests {
     }
     assert!(saw_stdout);
   }
+
+  #[cfg(unix)]
+  #[tokio::test]
+  async fn test_signal_termination() {
+    let output = Shell::exec("sh", &["-c", "kill -9 $$"], &CommandOptions::default())
+      .await
+      .unwrap();
+
+    assert!(!output.status.success);
+    assert_eq!(output.status.code, None);
+    assert_eq!(output.status.signal, Some(9));
+  }
+
+  #[cfg(unix)]
+  #[tokio::test]
+  async fn test_pty_output() {
+    let options = CommandOptions {
+      pty: Some(PtyOptions { cols: 80, rows: 24 }),
+      ..Default::default()
+    };
+
+    let output = Shell::exec("sh", &["-c", "test -t 1 && stty size"], &options)
+      .await
+      .unwrap();
+
+    assert!(output.status.success);
+    assert!(output.stdout.as_str().unwrap().contains("24 80"));
+  }
+
+  #[tokio::test]
+  async fn test_resize_requires_pty() {
+    let child = Shell::spawn(
+      if cfg!(windows) { "cmd" } else { "sh" },
+      [if cfg!(windows) { "/C" } else { "-c" }, "echo test"],
+      &CommandOptions::default(),
+    )
+    .unwrap();
+
+    let size = PtyOptions { cols: 80, rows: 24 };
+    assert!(matches!(child.resize(size), Err(crate::Error::PtyRequired)));
+  }
+
+  #[tokio::test]
+  async fn test_output_limit() {
+    let options = CommandOptions {
+      max_output_bytes: Some(4),
+      ..Default::default()
+    };
+
+    let output = Shell::exec(
+      if cfg!(windows) { "cmd" } else { "sh" },
+      &[if cfg!(windows) { "/C" } else { "-c" }, "echo hello world"],
+      &options,
+    )
+    .await
+    .unwrap();
+
+    assert!(output.status.success);
+    assert!(output.truncated);
+    assert_eq!(output.stdout.as_str().unwrap(), "hell");
+  }
+
+  #[cfg(unix)]
+  #[tokio::test]
+  async fn test_timeout() {
+    let options = CommandOptions {
+      timeout_ms: Some(100),
+      ..Default::default()
+    };
+
+    let output = Shell::exec("sh", &["-c", "echo started; sleep 10"], &options)
+      .await
+      .unwrap();
+
+    assert!(output.timed_out);
+    assert!(!output.status.success);
+    assert_eq!(output.stdout.as_str().unwrap(), "started\n");
+  }
+
+  #[cfg(unix)]
+  #[tokio::test]
+  async fn test_cancel() {
+    let result = Shell::exec_with_cancel(
+      "sleep",
+      &["10"],
+      &CommandOptions::default(),
+      tokio::time::sleep(Duration::from_millis(100)),
+    )
+    .await;
+
+    assert!(matches!(result, Err(crate::Error::Cancelled)));
+  }
 }
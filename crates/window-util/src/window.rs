@@ -1,17 +1,18 @@
-use once_cell::sync::OnceCell;
-use std::sync::Mutex;
+use once_cell::sync::Lazy;
+use std::sync::{Mutex, Once};
 use tokio::{
   sync::mpsc::{self, UnboundedReceiver, UnboundedSender},
   task,
   time::{Duration, sleep},
 };
 use windows::Win32::{
-  Foundation::HWND,
+  Foundation::{HWND, RECT},
   System::LibraryLoader::GetModuleHandleW,
   UI::{
     Accessibility::{SetWinEventHook, UnhookWinEvent},
     WindowsAndMessaging::{
-      DispatchMessageW, EVENT_SYSTEM_FOREGROUND, GetWindowTextLengthW, GetWindowTextW, MSG,
+      DispatchMessageW, EVENT_SYSTEM_FOREGROUND, GetDesktopWindow, GetForegroundWindow,
+      GetShellWindow, GetWindowRect, GetWindowTextLengthW, GetWindowTextW, IsZoomed, MSG,
       PM_REMOVE, PeekMessageW, SetForegroundWindow, TranslateMessage,
     },
   },
@@ -22,31 +23,110 @@ use windows::Win32::{
 pub struct WindowEvent {
   pub hwnd: isize,
   pub title: String,
+
+  /// Bounds of the window in physical pixels.
+  pub bounds: WindowBounds,
+
+  /// Whether the window is maximized.
+  pub is_maximized: bool,
+
+  /// Whether the window is the desktop or shell window.
+  pub is_desktop: bool,
+}
+
+impl WindowEvent {
+  /// Whether the window has no title or its title is ignored (see
+  /// `Window::set_ignored_titles`).
+  pub fn is_ignored(&self) -> bool {
+    self.title.is_empty()
+      || IGNORED_TITLES
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|title| self.title.contains(title.as_str()))
+  }
+}
+
+/// Bounds of a window in physical pixels.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct WindowBounds {
+  pub x: i32,
+  pub y: i32,
+  pub width: i32,
+  pub height: i32,
+}
+
+impl WindowBounds {
+  /// Whether these bounds fully cover the given rectangle.
+  pub fn covers(&self, x: i32, y: i32, width: i32, height: i32) -> bool {
+    self.x <= x
+      && self.y <= y
+      && self.x + self.width >= x + width
+      && self.y + self.height >= y + height
+  }
 }
 
 #[derive(Debug)]
 pub struct Window {
   event_rx: UnboundedReceiver<WindowEvent>,
-  #[allow(dead_code)]
   event_tx: UnboundedSender<WindowEvent>,
 }
 
-// Define EVENT_TX globally using OnceCell
-static EVENT_TX: OnceCell<Mutex<Option<UnboundedSender<WindowEvent>>>> = OnceCell::new();
+// Senders of all `Window` instances. Each instance receives every event.
+static EVENT_TXS: Lazy<Mutex<Vec<UnboundedSender<WindowEvent>>>> =
+  Lazy::new(|| Mutex::new(Vec::new()));
+
+// Ensures the event hook is only installed once.
+static LISTENER_INIT: Once = Once::new();
+
+/// Window titles to ignore by default. Matches on substrings.
+pub const DEFAULT_IGNORED_TITLES: &[&str] = &[
+  "EdgeBar - macos/macos",
+  "Dropdown - EdgeBar",
+  "Tauri App",
+  "DevTools",
+];
+
+// List of window titles to ignore, changeable at runtime.
+static IGNORED_TITLES: Lazy<Mutex<Vec<String>>> = Lazy::new(|| {
+  Mutex::new(
+    DEFAULT_IGNORED_TITLES
+      .iter()
+      .map(|title| title.to_string())
+      .collect(),
+  )
+});
 
 impl Window {
   pub fn new() -> crate::Result<Self> {
     let (event_tx, event_rx) = mpsc::unbounded_channel();
 
-    // Initialize the static EVENT_TX if not already initialized.
-    EVENT_TX.get_or_init(|| Mutex::new(None));
-    *EVENT_TX.get().unwrap().lock().unwrap() = Some(event_tx.clone());
-
-    Self::start_window_event_listener();
+    EVENT_TXS.lock().unwrap().push(event_tx.clone());
+    LISTENER_INIT.call_once(Self::start_window_event_listener);
 
     Ok(Window { event_rx, event_tx })
   }
-  /// Returns the next event from the `Window`.
+
+  /// Returns the current foreground window, regardless of its title, or
+  /// `None` if there is none.
+  pub fn foreground() -> Option<WindowEvent> {
+    let hwnd = unsafe { GetForegroundWindow() };
+
+    if hwnd.0.is_null() {
+      return None;
+    }
+
+    Some(window_event(hwnd))
+  }
+
+  /// Returns the next event from the `Window`, including events of
+  /// windows that have no title or whose title is ignored.
+  pub async fn all_events(&mut self) -> Option<WindowEvent> {
+    self.event_rx.recv().await
+  }
+
+  /// Returns the next event from the `Window`. Events of windows that
+  /// have no title or whose title is ignored are skipped.
   pub async fn events(&mut self) -> Option<WindowEvent> {
     while let Some(event) = self.event_rx.recv().await {
       if let Some(event) = self.on_event(event) {
@@ -67,9 +147,10 @@ impl Window {
   }
 
   fn on_event(&mut self, event: WindowEvent) -> Option<WindowEvent> {
-    // Example: Filter out events with empty titles
-    if event.title.is_empty() {
-      println!("Ignored event with empty title: {:?}", event);
+    // Filter out events of windows without a title or with an ignored
+    // title.
+    if event.is_ignored() {
+      println!("Ignored event for window: {:?}", event);
       return None;
     }
     Some(event)
@@ -101,6 +182,12 @@ impl Window {
     });
   }
 
+  /// Replaces the list of window titles to ignore events for. Matches on
+  /// substrings of the window title.
+  pub fn set_ignored_titles(titles: Vec<String>) {
+    *IGNORED_TITLES.lock().unwrap() = titles;
+  }
+
   pub fn set_foreground_window(hwnd: isize) -> anyhow::Result<(), String> {
     unsafe {
       let hwnd = HWND(hwnd as *mut _); // Convert `isize` to a raw pointer
@@ -115,9 +202,10 @@ impl Window {
 
 impl Drop for Window {
   fn drop(&mut self) {
-    if let Some(mutex) = EVENT_TX.get() {
-      *mutex.lock().unwrap() = None;
-    }
+    EVENT_TXS
+      .lock()
+      .unwrap()
+      .retain(|sender| !sender.same_channel(&self.event_tx));
   }
 }
 
@@ -167,33 +255,46 @@ unsafe extern "system" fn event_callback(
   _: u32,
   _: u32,
 ) {
-  // List of window titles to ignore
-  static IGNORED_TITLES: &[&str] = &["EdgeBar - macos/macos", "Dropdown - EdgeBar", "Tauri App", "DevTools"];
+  let event = window_event(hwnd);
+
+  // Send the event to all `Window` instances.
+  for sender in EVENT_TXS.lock().unwrap().iter() {
+    if let Err(err) = sender.send(event.clone()) {
+      eprintln!("Failed to send event: {}", err);
+    }
+  }
+}
 
+/// Creates a `WindowEvent` for the given window handle.
+///
+/// The title is empty if the window has none. Events are created for
+/// ignored titles as well (see `WindowEvent::is_ignored`).
+fn window_event(hwnd: HWND) -> WindowEvent {
   // Retrieve the window title
   let length = unsafe { GetWindowTextLengthW(hwnd) } + 1;
   let mut buffer = vec![0u16; length as usize];
-  let copied_length = unsafe { GetWindowTextW(hwnd, &mut buffer) };
+  let copied_length = unsafe { GetWindowTextW(hwnd, &mut buffer) }.max(0);
 
-  if copied_length > 0 {
-    let window_title = String::from_utf16_lossy(&buffer[..copied_length as usize]);
+  let window_title = String::from_utf16_lossy(&buffer[..copied_length as usize]);
 
-    // Ignore events if the window title contains any ignored substring
-    if IGNORED_TITLES.iter().any(|t| window_title.contains(t)) {
-      println!("Ignored window with title: {:?}", window_title);
-      return;
-    }
+  let mut rect = RECT::default();
+  let bounds = match unsafe { GetWindowRect(hwnd, &mut rect) } {
+    Ok(_) => WindowBounds {
+      x: rect.left,
+      y: rect.top,
+      width: rect.right - rect.left,
+      height: rect.bottom - rect.top,
+    },
+    Err(_) => WindowBounds::default(),
+  };
 
-    // Access the static `EVENT_TX` to send the event
-    if let Some(mutex) = EVENT_TX.get() {
-      if let Some(sender) = mutex.lock().unwrap().as_ref() {
-        if let Err(err) = sender.send(WindowEvent {
-          hwnd: hwnd.0 as isize,
-          title: window_title,
-        }) {
-          eprintln!("Failed to send event: {}", err);
-        }
-      }
-    }
+  let is_desktop = unsafe { hwnd == GetShellWindow() || hwnd == GetDesktopWindow() };
+
+  WindowEvent {
+    hwnd: hwnd.0 as isize,
+    title: window_title,
+    bounds,
+    is_maximized: unsafe { IsZoomed(hwnd).as_bool() },
+    is_desktop,
   }
 }
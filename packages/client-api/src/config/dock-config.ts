@@ -1,5 +1,13 @@
 export type DockConfig = {
   enabled: boolean;
   edge: 'top' | 'bottom' | 'left' | 'right' | null;
+  order?: number;
   windowMargin: string;
+  autoHide?: AutoHideConfig;
+};
+
+export type AutoHideConfig = {
+  enabled: boolean;
+  revealStrip?: string;
+  hideDelayMs?: number;
 };
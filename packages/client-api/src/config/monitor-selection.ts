@@ -1,3 +1,22 @@
-export type MonitorSelection = {
-  type: 'all' | 'primary' | 'secondary';
+export type MonitorSelection =
+  | {
+      type: 'all' | 'primary' | 'secondary';
+    }
+  | {
+      type: 'index';
+      match: number;
+    }
+  | {
+      type: 'name' | 'alias';
+      match: string;
+    }
+  | {
+      type: 'edid';
+      match: MonitorMatch;
+    };
+
+export type MonitorMatch = {
+  manufacturer?: string;
+  model?: string;
+  serial?: string;
 };
@@ -3,12 +3,15 @@ import type { WidgetPreset } from './widget-preset';
 import type { WidgetPrivileges } from './widget-privileges';
 
 export type WidgetConfig = {
+  version?: number;
+  extends?: string;
   htmlPath: string;
   zOrder: 'normal' | 'top_most' | 'bottom_most';
   shownInTaskbar: boolean;
   focused: boolean;
   resizable: boolean;
   transparent: boolean;
+  persistPlacement?: boolean;
   caching: WidgetCaching;
   privileges: WidgetPrivileges;
   presets: WidgetPreset[];
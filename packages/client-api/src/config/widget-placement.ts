@@ -16,4 +16,14 @@ export type WidgetPlacement = {
   height: string;
   monitorSelection: MonitorSelection;
   dockToEdge: DockConfig;
+  overrides?: PlacementOverride[];
 };
+
+export type PlacementOverride = {
+  monitorSelection: MonitorSelection;
+} & Partial<
+  Pick<
+    WidgetPlacement,
+    'anchor' | 'offsetX' | 'offsetY' | 'width' | 'height' | 'dockToEdge'
+  >
+>;
@@ -2,4 +2,7 @@ import type { WidgetPlacement } from './widget-placement';
 
 export type WidgetPreset = {
   name: string;
+  id?: string;
+  extends?: string;
+  hideOnFullscreen?: boolean;
 } & WidgetPlacement;
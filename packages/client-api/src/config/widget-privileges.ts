@@ -1,5 +1,6 @@
 export type WidgetPrivileges = {
   shellCommands: AllowedShellCommand[];
+  topics?: string[];
 };
 
 export type AllowedShellCommand = {
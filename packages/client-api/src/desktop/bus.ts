@@ -0,0 +1,79 @@
+import { listen, type Event, type UnlistenFn } from '@tauri-apps/api/event';
+
+import { desktopCommands, type BusMessage } from './desktop-commands';
+
+let listenPromise: Promise<UnlistenFn> | null = null;
+
+let callbacks: {
+  topic: string;
+  fn: (message: BusMessage<any>) => void;
+}[] = [];
+
+/**
+ * Publishes a message to other widgets subscribed to the topic.
+ *
+ * The topic must be allowed in the widget's `privileges.topics`. If
+ * `retain` is set, the message is also delivered to widgets that
+ * subscribe later on.
+ */
+export function publish<T = unknown>(
+  topic: string,
+  payload: T,
+  options: { retain?: boolean } = {},
+): Promise<void> {
+  return desktopCommands.busPublish(topic, payload, options.retain);
+}
+
+/**
+ * Subscribes to messages published to the topic.
+ *
+ * The callback is invoked immediately with the retained message for the
+ * topic, if any. Returns a function to unsubscribe.
+ */
+export async function subscribe<T = unknown>(
+  topic: string,
+  callback: (message: BusMessage<T>) => void,
+): Promise<() => Promise<void>> {
+  const unlisten = await (listenPromise ??
+    (listenPromise = listenBusMessage()));
+
+  const retained = await desktopCommands.busSubscribe(topic);
+
+  // Only register the callback once subscribed, such that it isn't kept
+  // around if subscribing fails (e.g. due to missing privileges).
+  callbacks.push({ topic, fn: callback });
+
+  if (retained) {
+    callback(retained as BusMessage<T>);
+  }
+
+  return async () => {
+    callbacks = callbacks.filter((entry) => entry.fn !== callback);
+
+    // Unsubscribe from the topic when there are no callbacks left for it.
+    if (!callbacks.some((entry) => entry.topic === topic)) {
+      await desktopCommands.busUnsubscribe(topic);
+    }
+
+    // Unlisten when there are no active callbacks.
+    if (callbacks.length === 0) {
+      unlisten();
+      listenPromise = null;
+    }
+  };
+}
+
+/**
+ * Create listener for bus messages.
+ *
+ * Only one Tauri event listener is needed to listen to all topics.
+ */
+async function listenBusMessage(): Promise<UnlistenFn> {
+  return listen('bus-message', (event: Event<BusMessage>) => {
+    callbacks.forEach((callback) => {
+      if (event.payload.topic === callback.topic) {
+        callback.fn(event.payload);
+      }
+    });
+  });
+}
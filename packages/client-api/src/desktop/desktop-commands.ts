@@ -7,17 +7,26 @@ import type { WidgetPlacement } from '~/config';
 const logger = createLogger();
 
 export const desktopCommands = {
+  widgetState,
   startWidget,
   startPreset,
+  setWidgetVisibility,
+  setPresetVisibility,
+  revealWidget,
   listenProvider,
   unlistenProvider,
   callProviderFunction,
   setAlwaysOnTop,
   setSkipTaskbar,
   shellExec,
+  shellCancel,
   shellSpawn,
   shellWrite,
+  shellResize,
   shellKill,
+  busPublish,
+  busSubscribe,
+  busUnsubscribe,
   setForegroundWindow,
   showMenu,
   resizeMenu,
@@ -71,6 +80,10 @@ export interface SystrayFunction {
   };
 }
 
+function widgetState(widgetId: string): Promise<WidgetStateResponse | null> {
+  return invoke<WidgetStateResponse | null>('widget_state', { widgetId });
+}
+
 function startWidget(
   configPath: string,
   placement: WidgetPlacement,
@@ -82,6 +95,32 @@ function startPreset(configPath: string, presetName: string): Promise<void> {
   return invoke<void>('start_preset', { configPath, presetName });
 }
 
+function setWidgetVisibility(
+  widgetId: string,
+  action: WidgetVisibilityAction,
+): Promise<void> {
+  return invoke<void>('set_widget_visibility', { widgetId, action });
+}
+
+function setPresetVisibility(
+  configPath: string,
+  presetName: string,
+  action: WidgetVisibilityAction,
+): Promise<void> {
+  return invoke<void>('set_preset_visibility', {
+    configPath,
+    presetName,
+    action,
+  });
+}
+
+function revealWidget(
+  widgetId: string,
+  action: WidgetVisibilityAction,
+): Promise<void> {
+  return invoke<void>('reveal_widget', { widgetId, action });
+}
+
 function listenProvider(args: {
   configHash: string;
   config: ProviderConfig;
@@ -115,14 +154,20 @@ function shellExec<TOutput extends string | Uint8Array = string>(
   program: string,
   args: string | string[] = [],
   options: ShellCommandOptions = {},
+  execId?: string,
 ): Promise<ShellExecOutput<TOutput>> {
   return invoke<ShellExecOutput<TOutput>>('shell_exec', {
     program,
     args,
     options,
+    execId,
   });
 }
 
+function shellCancel(execId: string): Promise<void> {
+  return invoke<void>('shell_cancel', { execId });
+}
+
 function shellSpawn(
   program: string,
   args: string | string[] = [],
@@ -138,10 +183,33 @@ function shellWrite(
   return invoke<void>('shell_write', { processId, buffer });
 }
 
+function shellResize(
+  processId: number,
+  size: ShellPtySize,
+): Promise<void> {
+  return invoke<void>('shell_resize', { pid: processId, size });
+}
+
 function shellKill(processId: number): Promise<void> {
   return invoke<void>('shell_kill', { processId });
 }
 
+function busPublish(
+  topic: string,
+  payload: unknown,
+  retain = false,
+): Promise<void> {
+  return invoke<void>('bus_publish', { topic, payload, retain });
+}
+
+function busSubscribe(topic: string): Promise<BusMessage | null> {
+  return invoke<BusMessage | null>('bus_subscribe', { topic });
+}
+
+function busUnsubscribe(topic: string): Promise<void> {
+  return invoke<void>('bus_unsubscribe', { topic });
+}
+
 function setForegroundWindow(hwnd: number): Promise<void> {
   return invoke<void>('set_foreground_window', { hwnd });
 }
@@ -176,6 +244,23 @@ function hideMenu(): Promise<void> {
   return invoke<void>('hide_menu');
 }
 
+export interface BusMessage<T = unknown> {
+  topic: string;
+  payload: T;
+  senderId: string;
+  retained: boolean;
+}
+
+export interface WidgetStateResponse {
+  id: string;
+  configPath: string;
+  htmlPath: string;
+  windowHandle: number | null;
+  isVisible: boolean;
+}
+
+export type WidgetVisibilityAction = 'show' | 'hide' | 'toggle';
+
 export interface ShellCommandOptions {
   /**
    * Current working directory.
@@ -199,6 +284,41 @@ export interface ShellCommandOptions {
    * (`Uint8Array`).
    */
   encoding?: ShellOutputEncoding;
+
+  /**
+   * Spawns the process in a pseudo-terminal of the given size instead of
+   * plain pipes. Needed for interactive programs that check whether they
+   * are attached to a terminal.
+   *
+   * Output is streamed as `stdout` events as soon as it is written,
+   * including terminal escape sequences.
+   */
+  pty?: ShellPtySize | null;
+
+  /**
+   * Time in milliseconds to wait for `shellExec` to finish. Once
+   * exceeded, `shellExec` resolves with `timedOut` set.
+   */
+  timeoutMs?: number | null;
+
+  /**
+   * Whether to kill the process once `timeoutMs` is exceeded. Otherwise,
+   * the process is left running in the background.
+   *
+   * Defaults to `true`.
+   */
+  killOnTimeout?: boolean;
+
+  /**
+   * Maximum number of bytes that `shellExec` collects from each of stdout
+   * and stderr. Any further output is discarded and `truncated` is set.
+   */
+  maxOutputBytes?: number | null;
+}
+
+export interface ShellPtySize {
+  cols: number;
+  rows: number;
 }
 
 export type ShellOutputEncoding =
@@ -218,6 +338,8 @@ export interface ShellExecOutput<TOutput extends string | Uint8Array = string> {
   signal: number | null;
   stdout: TOutput;
   stderr: TOutput;
+  timedOut: boolean;
+  truncated: boolean;
 }
 
 /**
@@ -1,3 +1,4 @@
+export * from './bus';
 export * from './desktop-commands';
 export * from './dialogs';
 export * from './monitors';
@@ -3,6 +3,7 @@ import {
   desktopCommands,
   type ShellCommandOptions,
   type ShellExecOutput,
+  type ShellPtySize,
 } from './desktop-commands';
 
 interface ShellEmission {
@@ -78,27 +79,66 @@ export async function hideMenu(): Promise<void> {
   return await desktopCommands.hideMenu();
 }
 
+export interface ShellExecOptions extends ShellCommandOptions {
+  /**
+   * Signal for cancelling the command. Aborting kills the process and
+   * rejects the pending `shellExec` call.
+   */
+  signal?: AbortSignal;
+}
+
 /**
  * Executes a shell command and waits for completion.
  *
  * @example
  * ```ts
- * const curl = await edgebar.shellExec('curl', 'https://www.google.com');
+ * const curl = await edgebar.shellExec('curl', 'https://www.google.com', {
+ *   timeoutMs: 5000,
+ *   maxOutputBytes: 1024 * 1024,
+ * });
  * console.log(curl.stdout);
+ *
+ * // Cancelling the command.
+ * const controller = new AbortController();
+ * const ping = edgebar.shellExec('ping', '127.0.0.1 -n 10', {
+ *   signal: controller.signal,
+ * });
+ * controller.abort();
  * ```
  *
  * @param {string} command - Path to program executable, or program name
  * (if in $PATH).
  * @param {string | string[]} args - Arguments to pass to the program.
  * @param {Object} options - Spawn options (optional).
- * @throws - If shell permissions are missing.
+ * @throws - If shell permissions are missing or the command is cancelled.
  */
 export async function shellExec<TOutput extends string | Uint8Array = string>(
   program: string,
   args?: string | string[],
-  options?: ShellCommandOptions,
+  options?: ShellExecOptions,
 ): Promise<ShellExecOutput<TOutput>> {
-  return await desktopCommands.shellExec(program, args, options);
+  const { signal, ...commandOptions } = options ?? {};
+
+  if (!signal) {
+    return await desktopCommands.shellExec(program, args, commandOptions);
+  }
+
+  signal.throwIfAborted();
+
+  const execId = crypto.randomUUID();
+  const onAbort = () => desktopCommands.shellCancel(execId);
+  signal.addEventListener('abort', onAbort, { once: true });
+
+  try {
+    return await desktopCommands.shellExec(
+      program,
+      args,
+      commandOptions,
+      execId,
+    );
+  } finally {
+    signal.removeEventListener('abort', onAbort);
+  }
 }
 
 /**
@@ -116,6 +156,12 @@ export async function shellExec<TOutput extends string | Uint8Array = string>(
  * // Interacting with the process.
  * ping.write('Hello, world!');
  * ping.kill();
+ *
+ * // Programs spawned in a pseudo-terminal can also be resized.
+ * const btop = await edgebar.shellSpawn('btop', [], {
+ *   pty: { cols: 80, rows: 24 },
+ * });
+ * btop.resize({ cols: 120, rows: 40 });
  * ```
  *
  * @param {string} command - Path to program executable, or program name
@@ -175,6 +221,7 @@ export async function shellSpawn<TOutput extends string | Uint8Array = string>(
     onExit: (callback) => exitCallbacks.push(callback),
     kill: () => desktopCommands.shellKill(processId),
     write: (data) => desktopCommands.shellWrite(processId, data),
+    resize: (size) => desktopCommands.shellResize(processId, size),
   };
 }
 
@@ -190,4 +237,5 @@ export interface ShellProcess<TOutput extends string | Uint8Array = string> {
   ) => void;
   kill: () => void;
   write: (data: string | Uint8Array) => void;
+  resize: (size: ShellPtySize) => void;
 }
@@ -1,6 +1,9 @@
 import { join } from '@tauri-apps/api/path';
 
-import { desktopCommands } from './desktop-commands';
+import {
+  desktopCommands,
+  type WidgetVisibilityAction,
+} from './desktop-commands';
 import type { WidgetPlacement } from '~/config';
 import { currentWindow, type WidgetWindow } from './windows';
 
@@ -60,8 +63,8 @@ export async function startWidget(
   configPath: string,
   placement: WidgetPlacement,
 ) {
-  // Ensure the config path ends with '.edgebar.json'.
-  const filePath = configPath.endsWith('.edgebar.json')
+  // Ensure the config path ends with a widget config extension.
+  const filePath = /\.edgebar\.(json|jsonc|toml)$/.test(configPath)
     ? configPath
     : `${configPath}.edgebar.json`;
 
@@ -79,8 +82,8 @@ export async function startWidgetPreset(
   configPath: string,
   presetName: string,
 ) {
-  // Ensure the config path ends with '.edgebar.json'.
-  const filePath = configPath.endsWith('.edgebar.json')
+  // Ensure the config path ends with a widget config extension.
+  const filePath = /\.edgebar\.(json|jsonc|toml)$/.test(configPath)
     ? configPath
     : `${configPath}.edgebar.json`;
 
@@ -88,3 +91,40 @@ export async function startWidgetPreset(
 
   return desktopCommands.startPreset(absolutePath, presetName);
 }
+
+/**
+ * Gets an open widget by its ID (e.g. `widget-starter_vanilla-default-0`).
+ *
+ * Returns `null` if no widget with the ID is open.
+ */
+export async function getWidgetById(
+  id: string,
+): Promise<Omit<Widget, 'window'> | null> {
+  const state = await desktopCommands.widgetState(id);
+
+  return state
+    ? { id: state.id, configPath: state.configPath, htmlPath: state.htmlPath }
+    : null;
+}
+
+/**
+ * Shows, hides or toggles an open widget by its ID without closing it.
+ */
+export function setWidgetVisibility(
+  id: string,
+  action: WidgetVisibilityAction,
+): Promise<void> {
+  return desktopCommands.setWidgetVisibility(id, action);
+}
+
+/**
+ * Reveals, hides or toggles a docked widget that is set to auto-hide.
+ *
+ * A widget revealed this way stays revealed until it is hidden again.
+ */
+export function revealWidget(
+  id: string,
+  action: WidgetVisibilityAction = 'toggle',
+): Promise<void> {
+  return desktopCommands.revealWidget(id, action);
+}
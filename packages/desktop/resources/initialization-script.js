@@ -11,7 +11,10 @@ setInterval(
   1000 * 60 * 15,
 );
 
-if (window.location.host === "127.0.0.1:6124") {
+if (
+  window.location.hostname === "127.0.0.1" &&
+  window.location.port === String(window.__EDGEBAR_ASSET_SERVER_PORT)
+) {
   if ("serviceWorker" in navigator) {
     navigator.serviceWorker
       .register("/__EDGEBAR/sw.js", { scope: "/" })
@@ -2,7 +2,10 @@ use std::{
   collections::HashMap,
   io::Cursor,
   path::{Path, PathBuf},
-  sync::LazyLock,
+  sync::{
+    atomic::{AtomicU16, Ordering},
+    LazyLock,
+  },
 };
 
 use rocket::{
@@ -17,17 +20,22 @@ use uuid::Uuid;
 
 use crate::common::PathExt;
 
-/// Port for the localhost asset server.
-const ASSET_SERVER_PORT: u16 = 6124;
+/// Default port for the localhost asset server.
+pub const DEFAULT_ASSET_SERVER_PORT: u16 = 6124;
+
+/// Port that the localhost asset server is running on.
+static ASSET_SERVER_PORT: AtomicU16 = AtomicU16::new(DEFAULT_ASSET_SERVER_PORT);
 
 /// Map of tokens to their corresponding path.
 static ASSET_SERVER_TOKENS: LazyLock<Mutex<HashMap<String, PathBuf>>> =
   LazyLock::new(|| Mutex::new(HashMap::new()));
 
-pub fn setup_asset_server() {
+pub fn setup_asset_server(port: u16) {
+  ASSET_SERVER_PORT.store(port, Ordering::Relaxed);
+
   task::spawn(async move {
     let rocket = rocket::build()
-      .configure(rocket::Config::figment().merge(("port", ASSET_SERVER_PORT)))
+      .configure(rocket::Config::figment().merge(("port", port)))
       .mount("/", routes![sw_js, normalize_css, init, serve]);
 
     if let Err(err) = rocket.launch().await {
@@ -36,6 +44,11 @@ pub fn setup_asset_server() {
   });
 }
 
+/// Returns the port that the localhost asset server was started on.
+pub fn asset_server_port() -> u16 {
+  ASSET_SERVER_PORT.load(Ordering::Relaxed)
+}
+
 pub async fn create_init_url(parent_dir: &Path, html_path: &Path) -> anyhow::Result<tauri::Url> {
   // Generate a unique token to identify requests from the widget to the
   // asset server.
@@ -47,7 +60,7 @@ pub async fn create_init_url(parent_dir: &Path, html_path: &Path) -> anyhow::Res
   );
 
   let url = tauri::Url::parse_with_params(
-    &format!("http://127.0.0.1:{}/__EDGEBAR/init", ASSET_SERVER_PORT),
+    &format!("http://127.0.0.1:{}/__EDGEBAR/init", asset_server_port()),
     &[("token", &token), ("redirect", &redirect)],
   )?;
 
@@ -1,8 +1,13 @@
 use std::{path::PathBuf, process};
 
+use anyhow::Context;
 use clap::{Args, Parser, Subcommand, ValueEnum};
 
-use crate::{common::LengthValue, config::AnchorPoint};
+use crate::{
+  common::LengthValue,
+  config::{AnchorPoint, MonitorSelection},
+  widget_factory::VisibilityAction,
+};
 
 const VERSION: &'static str = env!("VERSION_NUMBER");
 
@@ -42,6 +47,37 @@ pub enum CliCommand {
   /// Starts EdgeBar if it is not already running.
   Startup(StartupArgs),
 
+  /// Shows a hidden widget by its ID or preset, e.g.
+  /// `edgebar show --id widget-starter_vanilla-default-DP-1`.
+  ///
+  /// Requires an already running instance of EdgeBar.
+  Show(WidgetVisibilityArgs),
+
+  /// Hides a widget by its ID or preset without closing it, e.g.
+  /// `edgebar hide --path starter/vanilla --preset default`.
+  ///
+  /// Requires an already running instance of EdgeBar.
+  Hide(WidgetVisibilityArgs),
+
+  /// Shows a widget if hidden, or otherwise hides it.
+  ///
+  /// Requires an already running instance of EdgeBar.
+  Toggle(WidgetVisibilityArgs),
+
+  /// Reveals or hides a docked widget that is set to auto-hide, e.g.
+  /// `edgebar reveal --id widget-bar --action show`.
+  ///
+  /// Toggles the widget if no action is given. Requires an already
+  /// running instance of EdgeBar.
+  Reveal(RevealArgs),
+
+  /// Switches to a profile defined in the settings file, e.g.
+  /// `edgebar profile presentation`.
+  ///
+  /// Closes widgets that are not part of the profile and opens the
+  /// missing ones. Starts EdgeBar if it is not already running.
+  Profile(ProfileArgs),
+
   /// Retrieves and outputs a specific part of the state.
   ///
   /// Requires an already running instance of EdgeBar.
@@ -86,15 +122,57 @@ pub struct StartWidgetArgs {
   /// Monitor(s) to place the widget on.
   #[clap(long)]
   pub monitor_type: MonitorType,
+
+  /// Index, name or alias of the monitor to place the widget on, e.g.
+  /// `--monitor-type alias --monitor work-left`.
+  #[clap(
+    long,
+    required_if_eq_any([
+      ("monitor_type", "index"),
+      ("monitor_type", "name"),
+      ("monitor_type", "alias"),
+    ])
+  )]
+  pub monitor: Option<String>,
+}
+
+impl StartWidgetArgs {
+  /// Gets the monitor selection from the monitor type and value.
+  pub fn monitor_selection(&self) -> anyhow::Result<MonitorSelection> {
+    let monitor = || {
+      self
+        .monitor
+        .clone()
+        .context("A `--monitor` value is required for this monitor type.")
+    };
+
+    Ok(match self.monitor_type {
+      MonitorType::All => MonitorSelection::All,
+      MonitorType::Primary => MonitorSelection::Primary,
+      MonitorType::Secondary => MonitorSelection::Secondary,
+      MonitorType::Index => MonitorSelection::Index(
+        monitor()?
+          .parse()
+          .context("Monitor index must be a non-negative integer.")?,
+      ),
+      MonitorType::Name => MonitorSelection::Name(monitor()?),
+      MonitorType::Alias => MonitorSelection::Alias(monitor()?),
+    })
+  }
 }
 
-/// TODO: Add support for `Index` and `Name` types.
 #[derive(Clone, Debug, PartialEq, ValueEnum)]
 #[clap(rename_all = "snake_case")]
 pub enum MonitorType {
   All,
   Primary,
   Secondary,
+  /// Monitor at the given index, sorted left-to-right and top-to-bottom.
+  Index,
+  /// Monitor with the given device name.
+  Name,
+  /// Monitor matching the given alias from the settings.
+  Alias,
 }
 
 #[derive(Args, Clone, Debug, PartialEq)]
@@ -118,6 +196,42 @@ pub struct StartupArgs {
   pub config_dir: Option<PathBuf>,
 }
 
+#[derive(Args, Clone, Debug, PartialEq)]
+pub struct WidgetVisibilityArgs {
+  /// ID of the widget.
+  #[clap(
+    long,
+    conflicts_with = "config_path",
+    required_unless_present = "config_path"
+  )]
+  pub id: Option<String>,
+
+  /// Relative file path to widget config within the EdgeBar config
+  /// directory.
+  #[clap(long = "path", requires = "preset_name", value_hint = clap::ValueHint::FilePath)]
+  pub config_path: Option<PathBuf>,
+
+  /// Name of the preset within the target widget config.
+  #[clap(long = "preset", requires = "config_path")]
+  pub preset_name: Option<String>,
+}
+
+#[derive(Args, Clone, Debug, PartialEq)]
+pub struct RevealArgs {
+  #[clap(flatten)]
+  pub widget: WidgetVisibilityArgs,
+
+  /// Whether to reveal, hide or toggle the widget.
+  #[clap(long, value_enum, default_value = "toggle")]
+  pub action: VisibilityAction,
+}
+
+#[derive(Args, Clone, Debug, PartialEq)]
+pub struct ProfileArgs {
+  /// Name of the profile within the settings file.
+  pub name: String,
+}
+
 #[derive(Clone, Debug, Parser, PartialEq)]
 pub enum QueryArgs {
   /// Outputs available monitors.
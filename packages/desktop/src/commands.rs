@@ -1,15 +1,21 @@
 use serde_json::Value;
 use std::{collections::HashMap, path::PathBuf, sync::Arc};
 
+#[cfg(target_os = "windows")]
 use crate::common::windows::WindowExtWindows;
 use crate::{
   config::{Config, WidgetConfig, WidgetPlacement},
+  message_bus::{BusMessage, MessageBus},
   providers::{ProviderConfig, ProviderFunction, ProviderFunctionResponse, ProviderManager},
   shell_state::{ShellCommandArgs, ShellState},
-  widget_factory::{WidgetFactory, WidgetOpenOptions, WidgetState},
+  widget_factory::{VisibilityAction, WidgetFactory, WidgetOpenOptions, WidgetState},
 };
+#[cfg(target_os = "windows")]
 use menu_util::{ButtonPosition, MonitorPosition};
-use tauri::{AppHandle, State, Window};
+#[cfg(target_os = "windows")]
+use tauri::AppHandle;
+use tauri::{State, Window};
+#[cfg(target_os = "windows")]
 use window_util::Window as UtilWindow;
 
 #[tauri::command]
@@ -26,6 +32,14 @@ pub async fn widget_states(
   Ok(widget_factory.states().await)
 }
 
+#[tauri::command]
+pub async fn widget_state(
+  widget_id: String,
+  widget_factory: State<'_, Arc<WidgetFactory>>,
+) -> Result<Option<WidgetState>, String> {
+  Ok(widget_factory.state_by_id(&widget_id).await)
+}
+
 #[tauri::command]
 pub async fn start_widget(
   config_path: String,
@@ -68,6 +82,43 @@ pub async fn stop_preset(
     .map_err(|err| err.to_string())
 }
 
+#[tauri::command]
+pub async fn set_widget_visibility(
+  widget_id: String,
+  action: VisibilityAction,
+  widget_factory: State<'_, Arc<WidgetFactory>>,
+) -> anyhow::Result<(), String> {
+  widget_factory
+    .set_visibility_by_id(&widget_id, action)
+    .await
+    .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+pub async fn set_preset_visibility(
+  config_path: String,
+  preset_name: String,
+  action: VisibilityAction,
+  widget_factory: State<'_, Arc<WidgetFactory>>,
+) -> anyhow::Result<(), String> {
+  widget_factory
+    .set_visibility_by_preset(&PathBuf::from(config_path), &preset_name, action)
+    .await
+    .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+pub async fn reveal_widget(
+  widget_id: String,
+  action: VisibilityAction,
+  widget_factory: State<'_, Arc<WidgetFactory>>,
+) -> anyhow::Result<(), String> {
+  widget_factory
+    .reveal_by_id(&widget_id, action)
+    .await
+    .map_err(|err| err.to_string())
+}
+
 #[tauri::command]
 pub async fn update_widget_config(
   config_path: String,
@@ -126,10 +177,16 @@ pub fn set_always_on_top(window: Window) -> anyhow::Result<(), String> {
 
 #[tauri::command]
 pub fn set_skip_taskbar(window: Window, skip: bool) -> anyhow::Result<(), String> {
+  #[cfg(target_os = "windows")]
   window
     .set_tool_window(skip)
     .map_err(|err| err.to_string())?;
 
+  #[cfg(not(target_os = "windows"))]
+  window
+    .set_skip_taskbar(skip)
+    .map_err(|err| err.to_string())?;
+
   Ok(())
 }
 
@@ -138,16 +195,28 @@ pub async fn shell_exec(
   program: String,
   args: ShellCommandArgs,
   options: shell_util::CommandOptions,
+  exec_id: Option<String>,
   window: Window,
   shell_state: State<'_, ShellState>,
 ) -> anyhow::Result<shell_util::ShellExecOutput, String> {
   let widget_id = window.label();
   shell_state
-    .exec(&widget_id, &program, args, &options)
+    .exec(&widget_id, &program, args, &options, exec_id)
     .await
     .map_err(|err| err.to_string())
 }
 
+#[tauri::command]
+pub async fn shell_cancel(
+  exec_id: String,
+  window: Window,
+  shell_state: State<'_, ShellState>,
+) -> anyhow::Result<(), String> {
+  shell_state
+    .cancel(window.label(), &exec_id)
+    .map_err(|err| err.to_string())
+}
+
 #[tauri::command]
 pub async fn shell_spawn(
   program: String,
@@ -174,6 +243,15 @@ pub async fn shell_write(
     .map_err(|err| err.to_string())
 }
 
+#[tauri::command]
+pub async fn shell_resize(
+  pid: shell_util::ProcessId,
+  size: shell_util::PtyOptions,
+  shell_state: State<'_, ShellState>,
+) -> anyhow::Result<(), String> {
+  shell_state.resize(pid, size).map_err(|err| err.to_string())
+}
+
 #[tauri::command]
 pub async fn shell_kill(
   pid: shell_util::ProcessId,
@@ -182,6 +260,43 @@ pub async fn shell_kill(
   shell_state.kill(pid).map_err(|err| err.to_string())
 }
 
+#[tauri::command]
+pub async fn bus_publish(
+  topic: String,
+  payload: Value,
+  retain: Option<bool>,
+  window: Window,
+  message_bus: State<'_, MessageBus>,
+) -> anyhow::Result<(), String> {
+  message_bus
+    .publish(window.label(), &topic, payload, retain.unwrap_or(false))
+    .await
+    .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+pub async fn bus_subscribe(
+  topic: String,
+  window: Window,
+  message_bus: State<'_, MessageBus>,
+) -> anyhow::Result<Option<BusMessage>, String> {
+  message_bus
+    .subscribe(window.label(), &topic)
+    .await
+    .map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+pub async fn bus_unsubscribe(
+  topic: String,
+  window: Window,
+  message_bus: State<'_, MessageBus>,
+) -> anyhow::Result<(), String> {
+  message_bus.unsubscribe(window.label(), &topic).await;
+  Ok(())
+}
+
+#[cfg(target_os = "windows")]
 #[tauri::command]
 pub fn set_foreground_window(hwnd: isize) -> Result<String, String> {
   match UtilWindow::set_foreground_window(hwnd) {
@@ -196,21 +311,28 @@ pub fn set_foreground_window(hwnd: isize) -> Result<String, String> {
   }
 }
 
+#[cfg(target_os = "windows")]
 #[tauri::command]
-pub fn show_menu(
+pub async fn show_menu(
   sub_items: Vec<HashMap<String, Value>>,
   button: ButtonPosition,
   monitor: MonitorPosition,
+  window: Window,
   config: State<'_, Arc<Config>>,
+  widget_factory: State<'_, Arc<WidgetFactory>>,
 ) -> Result<String, String> {
   let app_handle: &AppHandle = config.app_handle();
 
+  // The menu window acts on behalf of the widget that opened it.
+  widget_factory.set_menu_owner(window.label()).await;
+
   match menu_util::show_menu(app_handle, sub_items, button, monitor) {
     Ok(_) => Ok(format!("Successfully shown menu")),
     Err(err) => Err(format!("Failed to show menu: {}", err)),
   }
 }
 
+#[cfg(target_os = "windows")]
 #[tauri::command]
 pub fn hide_menu(config: State<'_, Arc<Config>>) -> Result<String, String> {
   let app_handle: &AppHandle = config.app_handle();
@@ -221,6 +343,7 @@ pub fn hide_menu(config: State<'_, Arc<Config>>) -> Result<String, String> {
   }
 }
 
+#[cfg(target_os = "windows")]
 #[tauri::command]
 pub fn resize_menu(
   width: f64,
@@ -0,0 +1,137 @@
+/// Identity of a display as reported by its EDID.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EdidInfo {
+  /// Three-letter PNP manufacturer ID (e.g. `DEL`).
+  pub manufacturer: String,
+
+  /// Display name from the EDID descriptors, or the product code in hex
+  /// if the display doesn't report a name.
+  pub model: String,
+
+  /// Serial number string from the EDID descriptors, or the numeric
+  /// serial number if the display doesn't report a string.
+  pub serial: Option<String>,
+}
+
+/// Fixed header of every EDID base block.
+const EDID_HEADER: [u8; 8] = [0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00];
+
+/// Descriptor tag for the display product name.
+const DESCRIPTOR_NAME: u8 = 0xFC;
+
+/// Descriptor tag for the display serial number.
+const DESCRIPTOR_SERIAL: u8 = 0xFF;
+
+/// Parses the manufacturer, model and serial from an EDID base block.
+///
+/// Returns `None` if the data is too short or has an invalid header.
+pub fn parse_edid(edid: &[u8]) -> Option<EdidInfo> {
+  if edid.len() < 128 || edid[..8] != EDID_HEADER {
+    return None;
+  }
+
+  // Manufacturer ID is packed as three 5-bit letters (big-endian).
+  let packed = u16::from_be_bytes([edid[8], edid[9]]);
+  let manufacturer = [10, 5, 0]
+    .iter()
+    .map(|shift| (b'A' - 1 + ((packed >> shift) & 0x1F) as u8) as char)
+    .collect::<String>();
+
+  let product_code = u16::from_le_bytes([edid[10], edid[11]]);
+  let serial_number = u32::from_le_bytes([edid[12], edid[13], edid[14], edid[15]]);
+
+  let mut name = None;
+  let mut serial = None;
+
+  // Four 18-byte descriptors follow the detailed timing section. Display
+  // descriptors are marked by a zero pixel clock.
+  for descriptor in edid[54..126].chunks(18) {
+    if descriptor[..3] != [0, 0, 0] {
+      continue;
+    }
+
+    let text = String::from_utf8_lossy(&descriptor[5..])
+      .split('\n')
+      .next()
+      .unwrap_or_default()
+      .trim()
+      .to_string();
+
+    match descriptor[3] {
+      DESCRIPTOR_NAME if !text.is_empty() => name = Some(text),
+      DESCRIPTOR_SERIAL if !text.is_empty() => serial = Some(text),
+      _ => {}
+    }
+  }
+
+  Some(EdidInfo {
+    manufacturer,
+    model: name.unwrap_or_else(|| format!("{:04X}", product_code)),
+    serial: serial.or((serial_number != 0).then(|| serial_number.to_string())),
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// Creates an EDID base block with the given display descriptors.
+  fn edid_with_descriptors(descriptors: &[(u8, &str)]) -> Vec<u8> {
+    let mut edid = vec![0u8; 128];
+    edid[..8].copy_from_slice(&EDID_HEADER);
+
+    // "DEL" packed as 5-bit letters.
+    edid[8..10].copy_from_slice(&0x10ACu16.to_be_bytes());
+    edid[10..12].copy_from_slice(&0xA0C4u16.to_le_bytes());
+    edid[12..16].copy_from_slice(&12345u32.to_le_bytes());
+
+    for (index, (tag, text)) in descriptors.iter().enumerate() {
+      let offset = 54 + index * 18;
+      edid[offset + 3] = *tag;
+
+      let mut text = text.as_bytes().to_vec();
+      text.push(b'\n');
+      text.resize(13, b' ');
+      edid[offset + 5..offset + 18].copy_from_slice(&text);
+    }
+
+    edid
+  }
+
+  #[test]
+  fn test_parse_edid_descriptors() {
+    let edid = edid_with_descriptors(&[
+      (DESCRIPTOR_NAME, "DELL U2720Q"),
+      (DESCRIPTOR_SERIAL, "ABC123"),
+    ]);
+
+    assert_eq!(
+      parse_edid(&edid),
+      Some(EdidInfo {
+        manufacturer: "DEL".into(),
+        model: "DELL U2720Q".into(),
+        serial: Some("ABC123".into()),
+      })
+    );
+  }
+
+  #[test]
+  fn test_parse_edid_without_descriptors() {
+    let edid = edid_with_descriptors(&[]);
+
+    assert_eq!(
+      parse_edid(&edid),
+      Some(EdidInfo {
+        manufacturer: "DEL".into(),
+        model: "A0C4".into(),
+        serial: Some("12345".into()),
+      })
+    );
+  }
+
+  #[test]
+  fn test_parse_edid_invalid_header() {
+    assert_eq!(parse_edid(&[0u8; 128]), None);
+    assert_eq!(parse_edid(&EDID_HEADER), None);
+  }
+}
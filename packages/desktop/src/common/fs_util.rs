@@ -1,19 +1,180 @@
-use std::{fs, path::PathBuf};
+use std::{
+  fs,
+  io::Write,
+  path::{Path, PathBuf},
+};
 
 use anyhow::Context;
 use serde::de::DeserializeOwned;
+use serde_json::Value;
 
-/// Reads a JSON file and parses it into the specified type.
+use super::{patch_jsonc, strip_jsonc};
+
+/// File formats supported for config files.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ConfigFormat {
+  Json,
+  /// JSON with comments and trailing commas.
+  Jsonc,
+  Toml,
+}
+
+impl ConfigFormat {
+  /// All supported formats, in order of precedence.
+  pub const ALL: [ConfigFormat; 3] = [Self::Json, Self::Jsonc, Self::Toml];
+
+  /// Returns the file extension for the format (e.g. `json`).
+  pub fn extension(&self) -> &'static str {
+    match self {
+      Self::Json => "json",
+      Self::Jsonc => "jsonc",
+      Self::Toml => "toml",
+    }
+  }
+
+  /// Returns the format of a file based on its extension.
+  pub fn from_path(path: &Path) -> Option<Self> {
+    let extension = path.extension()?.to_str()?;
+
+    Self::ALL
+      .into_iter()
+      .find(|format| format.extension() == extension)
+  }
+
+  /// Parses the given file content into the specified type.
+  pub fn parse<T: DeserializeOwned>(&self, content: &str) -> anyhow::Result<T> {
+    Ok(match self {
+      Self::Json => serde_json::from_str(content)?,
+      Self::Jsonc => serde_json::from_str(&strip_jsonc(content))?,
+      Self::Toml => toml::from_str(content)?,
+    })
+  }
+
+  /// Serializes a value to file content.
+  ///
+  /// For JSONC, the previous file content is patched if given, such that
+  /// comments are preserved where possible.
+  pub fn serialize(&self, value: &Value, previous: Option<&str>) -> anyhow::Result<String> {
+    Ok(match (self, previous) {
+      (Self::Jsonc, Some(previous)) => patch_jsonc(previous, value)?,
+      (Self::Json | Self::Jsonc, _) => serde_json::to_string_pretty(value)? + "\n",
+      // TOML has no null type, so null values are omitted.
+      (Self::Toml, _) => toml::to_string_pretty(&without_nulls(value))?,
+    })
+  }
+}
+
+/// Reads a config file and parses it into the specified type.
+///
+/// The file format is inferred from the file extension.
 ///
 /// Returns the parsed type `T` if successful.
-pub fn read_and_parse_json<T: DeserializeOwned>(path: &PathBuf) -> anyhow::Result<T> {
+pub fn read_and_parse_config<T: DeserializeOwned>(path: &PathBuf) -> anyhow::Result<T> {
+  read_and_parse_config_hashed(path).map(|(parsed, _)| parsed)
+}
+
+/// Reads a config file and parses it into the specified type.
+///
+/// Returns the parsed type `T` and a hash of the content it was parsed
+/// from (see `content_hash`).
+pub fn read_and_parse_config_hashed<T: DeserializeOwned>(
+  path: &PathBuf,
+) -> anyhow::Result<(T, u64)> {
+  let format = ConfigFormat::from_path(path)
+    .with_context(|| format!("Unsupported config format: {}", path.display()))?;
+
   let content =
     fs::read_to_string(path).with_context(|| format!("Failed to read file: {}", path.display()))?;
 
-  let parsed = serde_json::from_str(&content)
-    .with_context(|| format!("Failed to parse JSON from file: {}", path.display()))?;
+  let parsed = format.parse(&content).with_context(|| {
+    format!(
+      "Failed to parse {} from file: {}",
+      format.extension().to_uppercase(),
+      path.display()
+    )
+  })?;
 
-  Ok(parsed)
+  Ok((parsed, content_hash(&content)))
+}
+
+/// Serializes a value and writes it to a config file.
+///
+/// The file format is inferred from the file extension. Comments are
+/// preserved for JSONC files where possible.
+///
+/// Returns the written file content.
+pub fn write_config(path: &PathBuf, value: &Value) -> anyhow::Result<String> {
+  let format = ConfigFormat::from_path(path)
+    .with_context(|| format!("Unsupported config format: {}", path.display()))?;
+
+  let previous = match format {
+    ConfigFormat::Jsonc => fs::read_to_string(path).ok(),
+    _ => None,
+  };
+
+  let content = format.serialize(value, previous.as_deref())?;
+  write_atomic(path, &content)?;
+
+  Ok(content)
+}
+
+/// Writes to a file by first writing to a temporary file in the same
+/// directory and then renaming it into place.
+///
+/// This prevents the file from being left partially written if the
+/// process is interrupted mid-write.
+pub fn write_atomic(path: &PathBuf, content: &str) -> anyhow::Result<()> {
+  let mut temp_name = path.file_name().unwrap_or_default().to_os_string();
+  temp_name.push(".tmp");
+  let temp_path = path.with_file_name(temp_name);
+
+  let write_res = fs::File::create(&temp_path)
+    .and_then(|mut file| {
+      file.write_all(content.as_bytes())?;
+      file.sync_all()
+    })
+    .and_then(|_| fs::rename(&temp_path, path));
+
+  if let Err(err) = write_res {
+    let _ = fs::remove_file(&temp_path);
+
+    return Err(err).with_context(|| format!("Failed to write file: {}", path.display()));
+  }
+
+  Ok(())
+}
+
+/// Returns a hash of the given content.
+///
+/// Uses 64-bit FNV-1a, which unlike `DefaultHasher` is stable across
+/// Rust versions, such that the hash can be used in persisted IDs.
+pub fn content_hash(content: &str) -> u64 {
+  content.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+    (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+  })
+}
+
+/// Reads a file and returns a hash of its content.
+pub fn read_content_hash(path: &PathBuf) -> anyhow::Result<u64> {
+  let content =
+    fs::read_to_string(path).with_context(|| format!("Failed to read file: {}", path.display()))?;
+
+  Ok(content_hash(&content))
+}
+
+/// Returns a copy of the JSON value with all null values removed.
+fn without_nulls(value: &Value) -> Value {
+  match value {
+    Value::Object(map) => Value::Object(
+      map
+        .iter()
+        .filter(|(_, item)| !item.is_null())
+        .map(|(key, item)| (key.clone(), without_nulls(item)))
+        .collect(),
+    ),
+    Value::Array(items) => Value::Array(items.iter().map(without_nulls).collect()),
+    _ => value.clone(),
+  }
 }
 
 /// Returns whether the path has the given extension.
@@ -50,3 +211,46 @@ pub fn copy_dir_all(
 
   Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::common::temp_dir;
+
+  #[test]
+  fn test_content_hash_is_stable() {
+    assert_eq!(content_hash(""), 0xcbf29ce484222325);
+    assert_eq!(content_hash("a"), 0xaf63dc4c8601ec8c);
+    assert_eq!(content_hash("foobar"), 0x85944171f73967e8);
+  }
+
+  #[test]
+  fn test_write_atomic_replaces_file() {
+    let dir = temp_dir();
+    let path = dir.join("config.json");
+
+    write_atomic(&path, "{}").unwrap();
+    write_atomic(&path, "{ \"version\": 1 }").unwrap();
+
+    assert_eq!(fs::read_to_string(&path).unwrap(), "{ \"version\": 1 }");
+    // The temporary file is renamed into place.
+    assert!(!dir.join("config.json.tmp").exists());
+
+    fs::remove_dir_all(dir).unwrap();
+  }
+
+  #[test]
+  fn test_write_atomic_cleans_up_on_error() {
+    let dir = temp_dir();
+
+    // Renaming a file onto a non-empty directory fails.
+    let path = dir.join("config.json");
+    fs::create_dir_all(path.join("child")).unwrap();
+
+    assert!(write_atomic(&path, "{}").is_err());
+    assert!(path.join("child").is_dir());
+    assert!(!dir.join("config.json.tmp").exists());
+
+    fs::remove_dir_all(dir).unwrap();
+  }
+}
@@ -0,0 +1,390 @@
+use std::{collections::HashMap, env, sync::LazyLock};
+
+use anyhow::{Context, bail};
+use regex::{Captures, Regex};
+use serde_json::Value;
+
+/// Matches variable references within a string (e.g. `${configDir}` or
+/// `${env:USERPROFILE}`), including escaped ones (e.g. `$${configDir}`).
+static VARIABLE_REGEX: LazyLock<Regex> =
+  LazyLock::new(|| Regex::new(r"(\$)?\$\{(env:)?([A-Za-z0-9_.\-]+)\}").unwrap());
+
+/// How variable values are escaped when interpolated into a string.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ValueEscape {
+  /// Values are inserted as-is.
+  Raw,
+
+  /// Values are escaped such that they match literally within a regular
+  /// expression (e.g. a Windows path containing `\` and `.`).
+  Regex,
+}
+
+impl ValueEscape {
+  fn apply(&self, value: &str) -> String {
+    match self {
+      Self::Raw => value.to_string(),
+      Self::Regex => regex::escape(value),
+    }
+  }
+}
+
+/// Replaces variable references in a string with their values.
+///
+/// Supports `${env:NAME}` for environment variables and `${name}` for
+/// entries in the given variables map. A reference can be escaped with
+/// an extra `$` (e.g. `$${name}` results in a literal `${name}`).
+///
+/// Example:
+/// ```ignore
+/// use std::collections::HashMap;
+///
+/// let vars = HashMap::from([("configDir".into(), "C:/edgebar".into())]);
+/// let path = interpolate_str("${configDir}/bin", &vars, ValueEscape::Raw)?; // "C:/edgebar/bin"
+/// ```
+pub fn interpolate_str(
+  input: &str,
+  variables: &HashMap<String, String>,
+  escape: ValueEscape,
+) -> anyhow::Result<String> {
+  let mut output = String::with_capacity(input.len());
+  let mut last_match_end = 0;
+
+  for captures in VARIABLE_REGEX.captures_iter(input) {
+    let full_match = captures.get(0).context("Invalid variable reference.")?;
+
+    output.push_str(&input[last_match_end..full_match.start()]);
+
+    match captures.get(1) {
+      // Escaped references are kept without the leading `$`.
+      Some(_) => output.push_str(&full_match.as_str()[1..]),
+      None => output.push_str(&escape.apply(&resolve_variable(&captures, variables)?)),
+    }
+
+    last_match_end = full_match.end();
+  }
+
+  output.push_str(&input[last_match_end..]);
+
+  Ok(output)
+}
+
+/// Resolves the value of a single variable reference.
+fn resolve_variable(
+  captures: &Captures,
+  variables: &HashMap<String, String>,
+) -> anyhow::Result<String> {
+  let name = &captures[3];
+
+  match captures.get(2) {
+    Some(_) => {
+      env::var(name).with_context(|| format!("Environment variable '{}' is not set.", name))
+    }
+    None => match variables.get(name) {
+      Some(value) => Ok(value.clone()),
+      None => bail!("Unknown variable '${{{}}}'.", name),
+    },
+  }
+}
+
+/// Interpolates the string values at the given paths within a JSON
+/// value. Other values are left as-is.
+///
+/// Paths consist of `/`-separated object keys, where `*` matches every
+/// item of an array (e.g. `presets/*/width`).
+pub fn interpolate_json(
+  value: &Value,
+  paths: &[&str],
+  variables: &HashMap<String, String>,
+  escape: ValueEscape,
+) -> anyhow::Result<Value> {
+  let mut value = value.clone();
+
+  map_json_strings(&mut value, paths, &mut |string| {
+    *string = interpolate_str(string, variables, escape)?;
+    Ok(())
+  })?;
+
+  Ok(value)
+}
+
+/// Replaces references to a single variable in a string with the given
+/// value. Other and escaped references are kept as-is.
+pub fn replace_variable(input: &str, name: &str, value: &str, escape: ValueEscape) -> String {
+  VARIABLE_REGEX
+    .replace_all(input, |captures: &Captures| {
+      let is_match = captures.get(1).is_none() && captures.get(2).is_none() && &captures[3] == name;
+
+      match is_match {
+        true => escape.apply(value),
+        false => captures[0].to_string(),
+      }
+    })
+    .into_owned()
+}
+
+/// Applies `f` to the string values at the given paths within a JSON
+/// value (see `interpolate_json` for the path syntax).
+pub fn map_json_strings<F>(value: &mut Value, paths: &[&str], f: &mut F) -> anyhow::Result<()>
+where
+  F: FnMut(&mut String) -> anyhow::Result<()>,
+{
+  for path in paths {
+    let segments = path.split('/').collect::<Vec<_>>();
+    map_strings_at(value, &segments, f)?;
+  }
+
+  Ok(())
+}
+
+/// Applies `f` to the string values at the given path segments.
+fn map_strings_at<F>(value: &mut Value, segments: &[&str], f: &mut F) -> anyhow::Result<()>
+where
+  F: FnMut(&mut String) -> anyhow::Result<()>,
+{
+  match (segments.split_first(), value) {
+    (None, Value::String(string)) => f(string)?,
+    (Some((&"*", rest)), Value::Array(items)) => {
+      for item in items {
+        map_strings_at(item, rest, f)?;
+      }
+    }
+    (Some((key, rest)), Value::Object(map)) => {
+      if let Some(item) = map.get_mut(*key) {
+        map_strings_at(item, rest, f)?;
+      }
+    }
+    _ => {}
+  }
+
+  Ok(())
+}
+
+/// Restores variable references from `template` into `value` at the
+/// given paths (see `interpolate_json`).
+///
+/// Strings in `value` that are equal to the interpolated string at the
+/// same location in `template` are replaced with the un-interpolated
+/// original. Changed values are kept as-is.
+pub fn restore_templates(
+  mut value: Value,
+  template: &Value,
+  paths: &[&str],
+  variables: &HashMap<String, String>,
+  escape: ValueEscape,
+) -> Value {
+  for path in paths {
+    let segments = path.split('/').collect::<Vec<_>>();
+    restore_at(&mut value, template, &segments, variables, escape);
+  }
+
+  value
+}
+
+/// Restores the variable references at the given path segments.
+fn restore_at(
+  value: &mut Value,
+  template: &Value,
+  segments: &[&str],
+  variables: &HashMap<String, String>,
+  escape: ValueEscape,
+) {
+  match (segments.split_first(), value, template) {
+    (None, Value::String(string), Value::String(template_str))
+      if interpolate_str(template_str, variables, escape)
+        .is_ok_and(|interpolated| interpolated == *string) =>
+    {
+      *string = template_str.clone();
+    }
+    (Some((&"*", rest)), Value::Array(items), Value::Array(template_items)) => {
+      for (item, template_item) in items.iter_mut().zip(template_items) {
+        restore_at(item, template_item, rest, variables, escape);
+      }
+    }
+    (Some((key, rest)), Value::Object(map), Value::Object(template_map)) => {
+      if let (Some(item), Some(template_item)) = (map.get_mut(*key), template_map.get(*key)) {
+        restore_at(item, template_item, rest, variables, escape);
+      }
+    }
+    _ => {}
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+
+  use super::*;
+
+  fn variables() -> HashMap<String, String> {
+    HashMap::from([
+      ("configDir".into(), "C:/edgebar".into()),
+      ("user".into(), "glaze".into()),
+    ])
+  }
+
+  #[test]
+  fn test_interpolate_str() {
+    assert_eq!(
+      interpolate_str("${configDir}/bin/${user}", &variables(), ValueEscape::Raw).unwrap(),
+      "C:/edgebar/bin/glaze"
+    );
+    assert_eq!(
+      interpolate_str("no variables", &variables(), ValueEscape::Raw).unwrap(),
+      "no variables"
+    );
+    assert!(interpolate_str("${unknown}", &variables(), ValueEscape::Raw).is_err());
+  }
+
+  #[test]
+  fn test_interpolate_str_env() {
+    assert_eq!(
+      interpolate_str("${env:PATH}", &variables(), ValueEscape::Raw).unwrap(),
+      env::var("PATH").unwrap()
+    );
+    assert!(
+      interpolate_str(
+        "${env:EDGEBAR_INTERPOLATION_UNSET}",
+        &variables(),
+        ValueEscape::Raw
+      )
+      .is_err()
+    );
+  }
+
+  #[test]
+  fn test_interpolate_str_escaped() {
+    assert_eq!(
+      interpolate_str(
+        "$${configDir} ${user} $${env:HOME}",
+        &variables(),
+        ValueEscape::Raw
+      )
+      .unwrap(),
+      "${configDir} glaze ${env:HOME}"
+    );
+    assert_eq!(
+      interpolate_str("$${unknown}", &variables(), ValueEscape::Raw).unwrap(),
+      "${unknown}"
+    );
+  }
+
+  #[test]
+  fn test_interpolate_json_paths() {
+    let value = json!({
+      "htmlPath": "${configDir}/index.html",
+      "title": "${user}",
+      "presets": [{ "width": "${user}" }, { "width": "100%" }],
+    });
+
+    let interpolated = interpolate_json(
+      &value,
+      &["htmlPath", "presets/*/width"],
+      &variables(),
+      ValueEscape::Raw,
+    );
+
+    assert_eq!(
+      interpolated.unwrap(),
+      json!({
+        "htmlPath": "C:/edgebar/index.html",
+        "title": "${user}",
+        "presets": [{ "width": "glaze" }, { "width": "100%" }],
+      })
+    );
+  }
+
+  #[test]
+  fn test_interpolate_json_missing_path() {
+    let value = json!({ "presets": [] });
+
+    assert_eq!(
+      interpolate_json(
+        &value,
+        &["htmlPath", "presets/*/width"],
+        &variables(),
+        ValueEscape::Raw
+      )
+      .unwrap(),
+      value
+    );
+  }
+
+  #[test]
+  fn test_replace_variable() {
+    assert_eq!(
+      replace_variable(
+        "${widgetDir}/${user}/$${widgetDir}",
+        "widgetDir",
+        "C:/base",
+        ValueEscape::Raw
+      ),
+      "C:/base/${user}/$${widgetDir}"
+    );
+    assert_eq!(
+      replace_variable("${env:widgetDir}", "widgetDir", "C:/base", ValueEscape::Raw),
+      "${env:widgetDir}"
+    );
+  }
+
+  #[test]
+  fn test_restore_templates() {
+    let template = json!({
+      "htmlPath": "${configDir}/index.html",
+      "presets": [{ "width": "${user}" }, { "width": "${user}" }],
+    });
+
+    let value = json!({
+      "htmlPath": "C:/edgebar/index.html",
+      "presets": [{ "width": "glaze" }, { "width": "50%" }, { "width": "glaze" }],
+    });
+
+    assert_eq!(
+      restore_templates(
+        value,
+        &template,
+        &["htmlPath", "presets/*/width"],
+        &variables(),
+        ValueEscape::Raw
+      ),
+      json!({
+        "htmlPath": "${configDir}/index.html",
+        "presets": [{ "width": "${user}" }, { "width": "50%" }, { "width": "glaze" }],
+      })
+    );
+  }
+
+  #[test]
+  fn test_restore_templates_escaped() {
+    let template = json!({ "htmlPath": "$${configDir}" });
+    let value = json!({ "htmlPath": "${configDir}" });
+
+    assert_eq!(
+      restore_templates(
+        value,
+        &template,
+        &["htmlPath"],
+        &variables(),
+        ValueEscape::Raw
+      ),
+      template
+    );
+  }
+
+  #[test]
+  fn test_interpolate_str_regex() {
+    let variables = HashMap::from([("widgetDir".into(), r"C:\Users\glaze\.edgebar".into())]);
+
+    let args_regex = interpolate_str(r"^${widgetDir}\\run\.ps1$", &variables, ValueEscape::Regex);
+    let args_regex = Regex::new(&args_regex.unwrap()).unwrap();
+
+    assert!(args_regex.is_match(r"C:\Users\glaze\.edgebar\run.ps1"));
+    // The `.` in the path only matches itself.
+    assert!(!args_regex.is_match(r"C:\Users\glaze\xedgebar\run.ps1"));
+
+    assert_eq!(
+      replace_variable("${widgetDir}", "widgetDir", "C:/a.b", ValueEscape::Regex),
+      r"C:/a\.b"
+    );
+  }
+}
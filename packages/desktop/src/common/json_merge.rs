@@ -0,0 +1,141 @@
+use serde_json::Value;
+
+/// Deep-merges `overlay` into `base`.
+///
+/// Objects are merged recursively, whereas any other value in `overlay`
+/// (including arrays) replaces the value in `base`.
+pub fn deep_merge(base: &mut Value, overlay: Value) {
+  match (base, overlay) {
+    (Value::Object(base_map), Value::Object(overlay_map)) => {
+      for (key, overlay_value) in overlay_map {
+        match base_map.get_mut(&key) {
+          Some(base_value) => deep_merge(base_value, overlay_value),
+          None => {
+            base_map.insert(key, overlay_value);
+          }
+        }
+      }
+    }
+    (base, overlay) => *base = overlay,
+  }
+}
+
+/// Removes fields from `value` that would otherwise be inherited from
+/// `inherited` when deep-merged.
+///
+/// Fields that are present in `own` are always kept, so that explicitly
+/// declared fields are not dropped even if equal to the inherited ones.
+pub fn strip_inherited(value: Value, inherited: &Value, own: &Value) -> Value {
+  let (Value::Object(map), Value::Object(inherited_map)) = (value.clone(), inherited) else {
+    return value;
+  };
+
+  let stripped = map
+    .into_iter()
+    .filter_map(|(key, item)| {
+      let own_item = own.get(&key);
+
+      match inherited_map.get(&key) {
+        None => Some((key, item)),
+        Some(inherited_item) if item.is_object() && inherited_item.is_object() => {
+          let item = strip_inherited(item, inherited_item, own_item.unwrap_or(&Value::Null));
+
+          let is_empty = item.as_object().is_some_and(|map| map.is_empty());
+
+          match is_empty && own_item.is_none() {
+            true => None,
+            false => Some((key, item)),
+          }
+        }
+        Some(inherited_item) => match *inherited_item == item && own_item.is_none() {
+          true => None,
+          false => Some((key, item)),
+        },
+      }
+    })
+    .collect();
+
+  Value::Object(stripped)
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+
+  use super::*;
+
+  #[test]
+  fn test_deep_merge_objects() {
+    let mut base = json!({
+      "a": 1,
+      "nested": { "b": 2, "c": [1, 2] },
+    });
+
+    deep_merge(
+      &mut base,
+      json!({ "nested": { "c": [3], "d": null }, "e": "new" }),
+    );
+
+    assert_eq!(
+      base,
+      json!({
+        "a": 1,
+        "nested": { "b": 2, "c": [3], "d": null },
+        "e": "new",
+      })
+    );
+  }
+
+  #[test]
+  fn test_deep_merge_replaces_non_objects() {
+    let mut base = json!({ "a": { "b": 1 } });
+    deep_merge(&mut base, json!({ "a": "replaced" }));
+    assert_eq!(base, json!({ "a": "replaced" }));
+
+    let mut base = json!([1, 2]);
+    deep_merge(&mut base, json!({ "a": 1 }));
+    assert_eq!(base, json!({ "a": 1 }));
+  }
+
+  #[test]
+  fn test_strip_inherited() {
+    let value = json!({
+      "a": 1,
+      "b": 2,
+      "nested": { "c": 3, "d": 4 },
+      "same": { "e": 5 },
+    });
+
+    let inherited = json!({
+      "a": 1,
+      "b": 1,
+      "nested": { "c": 3 },
+      "same": { "e": 5 },
+    });
+
+    assert_eq!(
+      strip_inherited(value, &inherited, &Value::Null),
+      json!({ "b": 2, "nested": { "d": 4 } })
+    );
+  }
+
+  #[test]
+  fn test_strip_inherited_keeps_own_fields() {
+    let value = json!({ "a": 1, "nested": { "c": 3 } });
+    let inherited = json!({ "a": 1, "nested": { "c": 3 } });
+    let own = json!({ "a": 1, "nested": {} });
+
+    assert_eq!(
+      strip_inherited(value.clone(), &inherited, &own),
+      json!({ "a": 1, "nested": {} })
+    );
+
+    // Stripped fields resolve back to the same value when merged.
+    let mut merged = inherited.clone();
+    deep_merge(
+      &mut merged,
+      strip_inherited(value.clone(), &inherited, &own),
+    );
+    assert_eq!(merged, value);
+  }
+}
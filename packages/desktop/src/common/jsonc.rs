@@ -0,0 +1,509 @@
+use std::collections::HashMap;
+
+use anyhow::Context;
+use serde_json::Value;
+
+/// Segment of a path to a value within a JSON document.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+enum PathSegment {
+  Key(String),
+  Index(usize),
+}
+
+/// Converts JSONC (JSON with comments and trailing commas) to plain JSON.
+///
+/// Comments and trailing commas are replaced with whitespace, such that
+/// byte offsets in the output match the ones in the input.
+pub fn strip_jsonc(content: &str) -> String {
+  let mut bytes = content.as_bytes().to_vec();
+  let mut index = 0;
+  let mut in_string = false;
+
+  // Blank out line and block comments.
+  while index < bytes.len() {
+    match (in_string, bytes[index], bytes.get(index + 1)) {
+      (true, b'\\', _) => index += 1,
+      (true, b'"', _) | (false, b'"', _) => in_string = !in_string,
+      (false, b'/', Some(b'/')) => {
+        while index < bytes.len() && bytes[index] != b'\n' {
+          bytes[index] = b' ';
+          index += 1;
+        }
+      }
+      (false, b'/', Some(b'*')) => {
+        while index < bytes.len() && !bytes[index..].starts_with(b"*/") {
+          if bytes[index] != b'\n' {
+            bytes[index] = b' ';
+          }
+          index += 1;
+        }
+
+        // Blank out the closing `*/`.
+        for _ in 0..2 {
+          if index < bytes.len() {
+            bytes[index] = b' ';
+            index += 1;
+          }
+        }
+
+        continue;
+      }
+      _ => {}
+    }
+
+    index += 1;
+  }
+
+  // Blank out commas that are followed by a closing bracket.
+  let mut index = 0;
+  let mut in_string = false;
+
+  while index < bytes.len() {
+    match (in_string, bytes[index]) {
+      (true, b'\\') => index += 1,
+      (_, b'"') => in_string = !in_string,
+      (false, b',') => {
+        let next_char = bytes[index + 1..]
+          .iter()
+          .find(|byte| !byte.is_ascii_whitespace());
+
+        if matches!(next_char, Some(b'}') | Some(b']')) {
+          bytes[index] = b' ';
+        }
+      }
+      _ => {}
+    }
+
+    index += 1;
+  }
+
+  // Only ASCII bytes within comments are replaced, so the output is
+  // still valid UTF-8.
+  String::from_utf8(bytes).unwrap_or_default()
+}
+
+/// Change to apply to a JSONC document.
+enum Edit<'a> {
+  /// Replace the value at the given path.
+  Replace(Vec<PathSegment>, &'a Value),
+
+  /// Insert new keys into the object at the given path.
+  Insert(Vec<PathSegment>, Vec<(&'a str, &'a Value)>),
+}
+
+/// Updates a JSONC document to the given value.
+///
+/// Only the values that have changed are rewritten, such that comments
+/// and formatting elsewhere in the document are preserved. Comments
+/// within a changed object or array are lost if keys are removed from it
+/// or if its length differs.
+pub fn patch_jsonc(content: &str, new_value: &Value) -> anyhow::Result<String> {
+  let stripped = strip_jsonc(content);
+  let old_value = serde_json::from_str::<Value>(&stripped).context("Invalid JSONC document.")?;
+
+  let mut spans = HashMap::new();
+  SpanParser::new(stripped.as_bytes()).parse(&mut vec![], &mut spans)?;
+
+  let mut edits = vec![];
+  collect_edits(&old_value, new_value, &mut vec![], &mut edits);
+
+  // Resolve edits to byte ranges and their replacement text.
+  let mut edits = edits
+    .into_iter()
+    .map(|edit| {
+      let path = match &edit {
+        Edit::Replace(path, _) | Edit::Insert(path, _) => path,
+      };
+
+      let (start, end) = spans
+        .get(path)
+        .copied()
+        .context("Missing span for value.")?;
+
+      let indent = indentation_at(content, start);
+
+      Ok(match edit {
+        Edit::Replace(_, value) => (start, end, serialize_indented(value, &indent)?),
+        Edit::Insert(_, members) => {
+          // Insert after the last member of the object, or directly after
+          // the opening brace if it is empty.
+          let last_pos = stripped[..end - 1]
+            .trim_end()
+            .len()
+            .checked_sub(1)
+            .context("Invalid object span.")?;
+
+          let is_empty = last_pos == start;
+
+          let member_indent = match is_empty {
+            true => format!("{}  ", indent),
+            false => indentation_at(content, last_pos),
+          };
+
+          let members = members
+            .into_iter()
+            .map(|(key, value)| {
+              Ok(format!(
+                "{}: {}",
+                serde_json::to_string(key)?,
+                serialize_indented(value, &member_indent)?
+              ))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?
+            .join(&format!(",\n{}", member_indent));
+
+          if is_empty {
+            let text = format!("\n{}{}\n{}", member_indent, members, indent);
+            return Ok((start + 1, start + 1, text));
+          }
+
+          // Keep a comment that trails the last member on the same line
+          // by inserting after the end of that line.
+          match trailing_line_end(content, last_pos + 1, end - 1) {
+            (Some(line_end), has_comma) => {
+              let trailing = &content[last_pos + 1..line_end];
+
+              let text = match has_comma {
+                true => format!("{}\n{}{},", trailing, member_indent, members),
+                false => format!(",{}\n{}{}", trailing, member_indent, members),
+              };
+
+              (last_pos + 1, line_end, text)
+            }
+            (None, _) => {
+              let text = format!(",\n{}{}", member_indent, members);
+              (last_pos + 1, last_pos + 1, text)
+            }
+          }
+        }
+      })
+    })
+    .collect::<anyhow::Result<Vec<_>>>()?;
+
+  // Apply edits back-to-front so that earlier spans stay valid.
+  edits.sort_by(|(a, _, _), (b, _, _)| b.cmp(a));
+
+  let mut patched = content.to_string();
+
+  for (start, end, text) in edits {
+    patched.replace_range(start..end, &text);
+  }
+
+  Ok(patched)
+}
+
+/// Collects the minimal set of edits to get from `old` to `new`.
+fn collect_edits<'a>(
+  old: &Value,
+  new: &'a Value,
+  path: &mut Vec<PathSegment>,
+  edits: &mut Vec<Edit<'a>>,
+) {
+  if old == new {
+    return;
+  }
+
+  match (old, new) {
+    (Value::Object(old_map), Value::Object(new_map))
+      if old_map.keys().all(|key| new_map.contains_key(key)) =>
+    {
+      let mut inserts = vec![];
+
+      for (key, new_item) in new_map {
+        match old_map.get(key) {
+          Some(old_item) => {
+            path.push(PathSegment::Key(key.clone()));
+            collect_edits(old_item, new_item, path, edits);
+            path.pop();
+          }
+          None => inserts.push((key.as_str(), new_item)),
+        }
+      }
+
+      if !inserts.is_empty() {
+        edits.push(Edit::Insert(path.clone(), inserts));
+      }
+    }
+    (Value::Array(old_items), Value::Array(new_items)) if old_items.len() == new_items.len() => {
+      for (index, (old_item, new_item)) in old_items.iter().zip(new_items).enumerate() {
+        path.push(PathSegment::Index(index));
+        collect_edits(old_item, new_item, path, edits);
+        path.pop();
+      }
+    }
+    _ => edits.push(Edit::Replace(path.clone(), new)),
+  }
+}
+
+/// Scans the whitespace, comments and commas between `from` and `to` for
+/// the end of the current line, skipping over comments.
+///
+/// Returns the offset of the line break (if any) and whether a comma was
+/// found before it.
+fn trailing_line_end(content: &str, from: usize, to: usize) -> (Option<usize>, bool) {
+  let bytes = content.as_bytes();
+  let mut index = from;
+  let mut has_comma = false;
+
+  while index < to {
+    match (bytes[index], bytes.get(index + 1)) {
+      (b'\n', _) => return (Some(index), has_comma),
+      (b',', _) => has_comma = true,
+      (b'/', Some(b'/')) => {
+        let line_end = content[index..to].find('\n').map(|offset| index + offset);
+        return (line_end, has_comma);
+      }
+      (b'/', Some(b'*')) => {
+        index = content[index + 2..to]
+          .find("*/")
+          .map_or(to, |offset| index + 2 + offset + 2);
+
+        continue;
+      }
+      _ => {}
+    }
+
+    index += 1;
+  }
+
+  (None, has_comma)
+}
+
+/// Pretty-prints a JSON value, indenting all lines after the first by the
+/// given indentation.
+fn serialize_indented(value: &Value, indent: &str) -> anyhow::Result<String> {
+  Ok(
+    serde_json::to_string_pretty(value)?
+      .lines()
+      .collect::<Vec<_>>()
+      .join(&format!("\n{}", indent)),
+  )
+}
+
+/// Returns the leading whitespace of the line containing the given byte
+/// offset.
+fn indentation_at(content: &str, offset: usize) -> String {
+  let line_start = content[..offset].rfind('\n').map_or(0, |index| index + 1);
+
+  content[line_start..]
+    .chars()
+    .take_while(|char| *char == ' ' || *char == '\t')
+    .collect()
+}
+
+/// Minimal parser for recording the byte spans of values within a valid
+/// JSON document.
+struct SpanParser<'a> {
+  bytes: &'a [u8],
+  pos: usize,
+}
+
+impl<'a> SpanParser<'a> {
+  fn new(bytes: &'a [u8]) -> Self {
+    Self { bytes, pos: 0 }
+  }
+
+  /// Parses the value at the current position and records its span, as
+  /// well as the spans of any nested values.
+  fn parse(
+    &mut self,
+    path: &mut Vec<PathSegment>,
+    spans: &mut HashMap<Vec<PathSegment>, (usize, usize)>,
+  ) -> anyhow::Result<()> {
+    self.skip_whitespace();
+    let start = self.pos;
+
+    match self.peek().context("Unexpected end of document.")? {
+      b'{' => {
+        self.pos += 1;
+
+        loop {
+          self.skip_whitespace();
+
+          match self.peek() {
+            Some(b'}') => break,
+            Some(b',') => self.pos += 1,
+            _ => {
+              let key = self.parse_string()?;
+              self.skip_whitespace();
+              self.expect(b':')?;
+
+              path.push(PathSegment::Key(key));
+              self.parse(path, spans)?;
+              path.pop();
+            }
+          }
+        }
+
+        self.pos += 1;
+      }
+      b'[' => {
+        self.pos += 1;
+        let mut index = 0;
+
+        loop {
+          self.skip_whitespace();
+
+          match self.peek() {
+            Some(b']') => break,
+            Some(b',') => self.pos += 1,
+            _ => {
+              path.push(PathSegment::Index(index));
+              self.parse(path, spans)?;
+              path.pop();
+              index += 1;
+            }
+          }
+        }
+
+        self.pos += 1;
+      }
+      b'"' => {
+        self.parse_string()?;
+      }
+      _ => {
+        while self
+          .peek()
+          .is_some_and(|byte| !matches!(byte, b',' | b'}' | b']') && !byte.is_ascii_whitespace())
+        {
+          self.pos += 1;
+        }
+      }
+    }
+
+    spans.insert(path.clone(), (start, self.pos));
+
+    Ok(())
+  }
+
+  /// Parses a string literal and returns its unescaped value.
+  fn parse_string(&mut self) -> anyhow::Result<String> {
+    let start = self.pos;
+    self.expect(b'"')?;
+
+    while let Some(byte) = self.peek() {
+      self.pos += 1;
+
+      match byte {
+        b'\\' => self.pos += 1,
+        b'"' => {
+          let literal = std::str::from_utf8(&self.bytes[start..self.pos])?;
+          return Ok(serde_json::from_str(literal)?);
+        }
+        _ => {}
+      }
+    }
+
+    anyhow::bail!("Unterminated string.")
+  }
+
+  fn expect(&mut self, expected: u8) -> anyhow::Result<()> {
+    match self.peek() {
+      Some(byte) if byte == expected => {
+        self.pos += 1;
+        Ok(())
+      }
+      _ => anyhow::bail!("Expected '{}' at offset {}.", expected as char, self.pos),
+    }
+  }
+
+  fn peek(&self) -> Option<u8> {
+    self.bytes.get(self.pos).copied()
+  }
+
+  fn skip_whitespace(&mut self) {
+    while self.peek().is_some_and(|byte| byte.is_ascii_whitespace()) {
+      self.pos += 1;
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+
+  use super::*;
+
+  #[test]
+  fn test_strip_jsonc_keeps_comments_in_strings() {
+    let content = r#"{ "url": "http://a//b", "glob": "/*.js" } // comment"#;
+
+    assert_eq!(
+      serde_json::from_str::<Value>(&strip_jsonc(content)).unwrap(),
+      json!({ "url": "http://a//b", "glob": "/*.js" })
+    );
+  }
+
+  #[test]
+  fn test_strip_jsonc_escaped_quotes() {
+    let content = r#"{ "a": "say \"hi\" // not a comment", "b": "\\" } // comment"#;
+
+    assert_eq!(
+      serde_json::from_str::<Value>(&strip_jsonc(content)).unwrap(),
+      json!({ "a": "say \"hi\" // not a comment", "b": "\\" })
+    );
+  }
+
+  #[test]
+  fn test_strip_jsonc_trailing_commas_and_block_comments() {
+    let content = "{\n  /* block\n  comment */ \"a\": [1, 2,],\n  \"b\": \"x\", /* c */\n}";
+    let stripped = strip_jsonc(content);
+
+    assert_eq!(stripped.len(), content.len());
+    assert_eq!(
+      serde_json::from_str::<Value>(&stripped).unwrap(),
+      json!({ "a": [1, 2], "b": "x" })
+    );
+  }
+
+  #[test]
+  fn test_patch_jsonc_preserves_comments() {
+    let content = "{\n  // Width of the bar.\n  \"width\": \"100%\", /* px */\n  \"height\": 40\n}";
+    let patched = patch_jsonc(content, &json!({ "width": "50%", "height": 40 })).unwrap();
+
+    assert_eq!(
+      patched,
+      "{\n  // Width of the bar.\n  \"width\": \"50%\", /* px */\n  \"height\": 40\n}"
+    );
+  }
+
+  #[test]
+  fn test_patch_jsonc_insert_into_empty_object() {
+    let content = "{\n  \"a\": {}\n}";
+    let patched = patch_jsonc(content, &json!({ "a": { "x": 1, "y": [2] } })).unwrap();
+
+    assert_eq!(
+      patched,
+      "{\n  \"a\": {\n    \"x\": 1,\n    \"y\": [\n      2\n    ]\n  }\n}"
+    );
+  }
+
+  #[test]
+  fn test_patch_jsonc_insert_multiple_keys() {
+    let content = "{\n  \"a\": 1\n}";
+    let new_value = json!({ "a": 1, "b": 2, "c": 3 });
+    let patched = patch_jsonc(content, &new_value).unwrap();
+
+    assert_eq!(patched, "{\n  \"a\": 1,\n  \"b\": 2,\n  \"c\": 3\n}");
+    assert_eq!(
+      serde_json::from_str::<Value>(&strip_jsonc(&patched)).unwrap(),
+      new_value
+    );
+  }
+
+  #[test]
+  fn test_patch_jsonc_insert_after_trailing_comment() {
+    let content = "{\n  \"a\": 1 // one\n}";
+    let patched = patch_jsonc(content, &json!({ "a": 1, "b": 2 })).unwrap();
+
+    assert_eq!(patched, "{\n  \"a\": 1, // one\n  \"b\": 2\n}");
+  }
+
+  #[test]
+  fn test_patch_jsonc_insert_with_trailing_comma() {
+    let content = "{\n  \"a\": 1, // one\n}";
+    let patched = patch_jsonc(content, &json!({ "a": 1, "b": 2 })).unwrap();
+
+    assert_eq!(patched, "{\n  \"a\": 1, // one\n  \"b\": 2,\n}");
+  }
+}
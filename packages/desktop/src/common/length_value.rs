@@ -4,8 +4,18 @@ use anyhow::{bail, Context};
 use regex::Regex;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
+/// A length that is resolved to pixels relative to a total length (e.g.
+/// the monitor width) and the viewport (i.e. the monitor).
+///
+/// Consists of one or more terms that are summed, such that lengths
+/// like `100% - 20px` can be expressed.
 #[derive(Debug, Clone, PartialEq)]
 pub struct LengthValue {
+  pub terms: Vec<LengthTerm>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LengthTerm {
   pub amount: f32,
   pub unit: LengthUnit,
 }
@@ -13,61 +23,174 @@ pub struct LengthValue {
 #[derive(Debug, Deserialize, Clone, PartialEq, Serialize)]
 #[serde(rename_all = "snake_case")]
 pub enum LengthUnit {
+  /// Percentage of the total length (`%`).
   Percentage,
+  /// Pixels that are scaled by the scale factor (`px`).
   Pixel,
+  /// Logical pixels (`lpx`). Same as `px`, but explicit about scaling.
+  LogicalPixel,
+  /// Physical pixels that ignore the scale factor (`ppx`).
+  PhysicalPixel,
+  /// Percentage of the viewport width (`vw`).
+  ViewportWidth,
+  /// Percentage of the viewport height (`vh`).
+  ViewportHeight,
+}
+
+impl LengthUnit {
+  /// Units in the order they are matched when parsing. Longer suffixes
+  /// come first to avoid matching `px` within `lpx`.
+  const ALL: [LengthUnit; 6] = [
+    Self::LogicalPixel,
+    Self::PhysicalPixel,
+    Self::Pixel,
+    Self::ViewportWidth,
+    Self::ViewportHeight,
+    Self::Percentage,
+  ];
+
+  /// Returns the suffix of the unit (e.g. `px`).
+  pub fn suffix(&self) -> &'static str {
+    match self {
+      Self::Percentage => "%",
+      Self::Pixel => "px",
+      Self::LogicalPixel => "lpx",
+      Self::PhysicalPixel => "ppx",
+      Self::ViewportWidth => "vw",
+      Self::ViewportHeight => "vh",
+    }
+  }
 }
 
 impl LengthValue {
-  pub fn to_px(&self, total_px: i32) -> i32 {
-    match self.unit {
-      LengthUnit::Percentage => (self.amount / 100. * total_px as f32) as i32,
-      LengthUnit::Pixel => self.amount as i32,
+  /// Creates a length value of a single pixel amount.
+  pub fn from_px(amount: f32) -> Self {
+    Self {
+      terms: vec![LengthTerm {
+        amount,
+        unit: LengthUnit::Pixel,
+      }],
     }
   }
 
-  pub fn to_px_scaled(&self, total_px: i32, scale_factor: f32) -> i32 {
-    match self.unit {
-      LengthUnit::Percentage => self.to_px(total_px),
-      LengthUnit::Pixel => (scale_factor * self.amount) as i32,
+  /// Creates a length value of a single physical pixel amount.
+  pub fn from_ppx(amount: f32) -> Self {
+    Self {
+      terms: vec![LengthTerm {
+        amount,
+        unit: LengthUnit::PhysicalPixel,
+      }],
     }
   }
+
+  #[cfg(test)]
+  pub fn to_px(&self, total_px: i32) -> i32 {
+    self.to_px_scaled(total_px, 1.)
+  }
+
+  /// Resolves the length in physical pixels. Viewport units are
+  /// relative to `total_px`.
+  #[cfg(test)]
+  pub fn to_px_scaled(&self, total_px: i32, scale_factor: f32) -> i32 {
+    self.to_px_in_viewport(total_px, total_px, total_px, scale_factor)
+  }
+
+  /// Resolves the length in physical pixels, with `vw` and `vh` units
+  /// relative to the given viewport size.
+  pub fn to_px_in_viewport(
+    &self,
+    total_px: i32,
+    viewport_width: i32,
+    viewport_height: i32,
+    scale_factor: f32,
+  ) -> i32 {
+    let px = self
+      .terms
+      .iter()
+      .map(|term| match term.unit {
+        LengthUnit::Percentage => term.amount / 100. * total_px as f32,
+        LengthUnit::Pixel | LengthUnit::LogicalPixel => scale_factor * term.amount,
+        LengthUnit::PhysicalPixel => term.amount,
+        LengthUnit::ViewportWidth => term.amount / 100. * viewport_width as f32,
+        LengthUnit::ViewportHeight => term.amount / 100. * viewport_height as f32,
+      })
+      .sum::<f32>();
+
+    px as i32
+  }
 }
 
 impl FromStr for LengthValue {
   type Err = anyhow::Error;
 
-  /// Parses a string containing a number followed by a unit (`px`, `%`).
-  /// Allows for negative numbers.
+  /// Parses a string containing one or more numbers followed by a unit
+  /// (`px`, `lpx`, `ppx`, `vw`, `vh`, `%`), joined by `+` or `-`. Allows for negative and
+  /// fractional numbers, and an optional `calc()` wrapper. Numbers
+  /// without a unit are treated as pixels.
   ///
   /// Example:
   /// ```
-  /// LengthValue::from_str("100px") // { amount: 100.0, unit: LengthUnit::Pixel }
+  /// LengthValue::from_str("100px") // [{ amount: 100.0, unit: LengthUnit::Pixel }]
+  /// LengthValue::from_str("calc(100% - 20px)") // [100%, -20px]
   /// ```
   fn from_str(unparsed: &str) -> anyhow::Result<Self> {
-    let units_regex = Regex::new(r"([+-]?\d+)(%|px)?")?;
-
     let err_msg = format!(
-      "Not a valid length value '{}'. Must be of format '10px' or '10%'.",
+      "Not a valid length value '{}'. Must be of format '10px', '12.5%' or '100% - 20px', \
+      with units px, lpx, ppx, vw, vh or %.",
       unparsed
     );
 
-    let captures = units_regex
-      .captures(unparsed)
-      .context(err_msg.to_string())?;
+    let trimmed = unparsed.trim();
+    let mut expression = trimmed
+      .strip_prefix("calc(")
+      .and_then(|expression| expression.strip_suffix(')'))
+      .unwrap_or(trimmed)
+      .trim();
+
+    // A term is an optional sign (or operator), a number, and an
+    // optional unit.
+    let term_regex = Regex::new(r"^([+-]?)\s*(\d+(?:\.\d+)?|\.\d+)(%|lpx|ppx|px|vw|vh)?")?;
+
+    let mut terms = vec![];
+
+    while !expression.is_empty() {
+      let captures = term_regex
+        .captures(expression)
+        .context(err_msg.to_string())?;
+
+      let sign = captures.get(1).map_or("", |m| m.as_str());
+
+      // Terms after the first need to be joined by an operator.
+      if !terms.is_empty() && sign.is_empty() {
+        bail!(err_msg);
+      }
 
-    let unit_str = captures.get(2).map_or("", |m| m.as_str());
-    let unit = match unit_str {
-      "px" | "" => LengthUnit::Pixel,
-      "%" => LengthUnit::Percentage,
-      _ => bail!(err_msg),
-    };
+      let amount = captures
+        .get(2)
+        .and_then(|amount_str| f32::from_str(amount_str.as_str()).ok())
+        .context(err_msg.to_string())?;
 
-    let amount = captures
-      .get(1)
-      .and_then(|amount_str| f32::from_str(amount_str.into()).ok())
-      .context(err_msg.to_string())?;
+      let unit = match captures.get(3).map_or("", |m| m.as_str()) {
+        "" => LengthUnit::Pixel,
+        suffix => LengthUnit::ALL
+          .into_iter()
+          .find(|unit| unit.suffix() == suffix)
+          .context(err_msg.to_string())?,
+      };
 
-    Ok(LengthValue { amount, unit })
+      terms.push(LengthTerm {
+        amount: if sign == "-" { -amount } else { amount },
+        unit,
+      });
+
+      expression = expression[captures.get(0).unwrap().end()..].trim_start();
+    }
+
+    if terms.is_empty() {
+      bail!(err_msg);
+    }
+
+    Ok(LengthValue { terms })
   }
 }
 
@@ -76,10 +199,17 @@ impl Serialize for LengthValue {
   where
     S: Serializer,
   {
-    let s = match self.unit {
-      LengthUnit::Percentage => format!("{}%", self.amount),
-      LengthUnit::Pixel => format!("{}px", self.amount),
-    };
+    let mut s = String::new();
+
+    for (index, term) in self.terms.iter().enumerate() {
+      let unit = term.unit.suffix();
+
+      match index {
+        0 => s.push_str(&format!("{}{}", term.amount, unit)),
+        _ if term.amount < 0. => s.push_str(&format!(" - {}{}", -term.amount, unit)),
+        _ => s.push_str(&format!(" + {}{}", term.amount, unit)),
+      }
+    }
 
     serializer.serialize_str(&s)
   }
@@ -97,9 +227,67 @@ impl<'de> Deserialize<'de> for LengthValue {
 
 impl Default for LengthValue {
   fn default() -> Self {
-    Self {
-      amount: 0.,
-      unit: LengthUnit::Pixel,
+    Self::from_px(0.)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse_fractional_percentage() {
+    let value = LengthValue::from_str("12.5%").unwrap();
+
+    assert_eq!(value.to_px(1000), 125);
+  }
+
+  #[test]
+  fn test_parse_calc_expression() {
+    let value = LengthValue::from_str("calc(100% - 20px)").unwrap();
+
+    assert_eq!(value, LengthValue::from_str("100%-20px").unwrap());
+    assert_eq!(value.to_px(1920), 1900);
+    assert_eq!(value.to_px_scaled(1920, 1.5), 1890);
+  }
+
+  #[test]
+  fn test_resolve_explicit_units() {
+    let value = LengthValue::from_str("32ppx + 32lpx").unwrap();
+    assert_eq!(value.to_px_scaled(1000, 1.5), 32 + 48);
+
+    let value = LengthValue::from_str("10vh + 10vw").unwrap();
+    assert_eq!(value.to_px_in_viewport(0, 1920, 1080, 1.), 108 + 192);
+  }
+
+  #[test]
+  fn test_rejects_invalid_values() {
+    for unparsed in ["", "abc", "10pxx", "10 20px", "10em", "calc(10px", "50% +"] {
+      assert!(
+        LengthValue::from_str(unparsed).is_err(),
+        "Expected '{}' to be rejected.",
+        unparsed
+      );
+    }
+  }
+
+  #[test]
+  fn test_serialize_round_trip() {
+    for unparsed in [
+      "-10px",
+      "12.5%",
+      "50% + 4px",
+      "100% - 20px",
+      "32lpx",
+      "32ppx",
+      "10vh - 2vw",
+    ] {
+      let value = LengthValue::from_str(unparsed).unwrap();
+
+      assert_eq!(
+        serde_json::to_value(&value).unwrap(),
+        serde_json::json!(unparsed)
+      );
     }
   }
 }
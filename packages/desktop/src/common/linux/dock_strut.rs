@@ -0,0 +1,153 @@
+use anyhow::Context;
+use tauri::{PhysicalPosition, PhysicalSize};
+use tracing::info;
+use x11rb::{
+  connection::Connection,
+  protocol::xproto::{AtomEnum, ConnectionExt as _, PropMode},
+  rust_connection::RustConnection,
+  wrapper::ConnectionExt as _,
+};
+
+use crate::config::DockEdge;
+
+/// Atoms used for reserving screen space as per the EWMH spec.
+struct DockAtoms {
+  strut: u32,
+  strut_partial: u32,
+  window_type: u32,
+  window_type_dock: u32,
+}
+
+impl DockAtoms {
+  fn intern(conn: &RustConnection) -> anyhow::Result<Self> {
+    let strut = conn.intern_atom(false, b"_NET_WM_STRUT")?;
+    let strut_partial = conn.intern_atom(false, b"_NET_WM_STRUT_PARTIAL")?;
+    let window_type = conn.intern_atom(false, b"_NET_WM_WINDOW_TYPE")?;
+    let window_type_dock = conn.intern_atom(false, b"_NET_WM_WINDOW_TYPE_DOCK")?;
+
+    Ok(Self {
+      strut: strut.reply()?.atom,
+      strut_partial: strut_partial.reply()?.atom,
+      window_type: window_type.reply()?.atom,
+      window_type_dock: window_type_dock.reply()?.atom,
+    })
+  }
+}
+
+/// Marks the given X11 window as a dock window.
+///
+/// Window managers generally only read the window type when a window is
+/// mapped, so this should be set once rather than with every strut.
+pub fn set_dock_window_type(window_id: u32) -> anyhow::Result<()> {
+  let (conn, _) = x11rb::connect(None)?;
+  let atoms = DockAtoms::intern(&conn)?;
+
+  conn
+    .change_property32(
+      PropMode::REPLACE,
+      window_id,
+      atoms.window_type,
+      AtomEnum::ATOM,
+      &[atoms.window_type_dock],
+    )?
+    .check()?;
+
+  Ok(())
+}
+
+/// Reserves screen space for the given X11 window by setting
+/// `_NET_WM_STRUT_PARTIAL`, such that EWMH window managers keep other
+/// windows out of the reserved space.
+///
+/// Unlike app bars on Windows, the window manager doesn't adjust the
+/// reserved space, so the given size and position are returned as-is.
+pub fn create_dock_strut(
+  window_id: u32,
+  size: PhysicalSize<i32>,
+  position: PhysicalPosition<i32>,
+  edge: DockEdge,
+) -> anyhow::Result<(PhysicalSize<i32>, PhysicalPosition<i32>)> {
+  let (conn, screen_num) = x11rb::connect(None)?;
+  let screen = conn
+    .setup()
+    .roots
+    .get(screen_num)
+    .context("No X11 screen available.")?;
+
+  let root_width = screen.width_in_pixels as i32;
+  let root_height = screen.height_in_pixels as i32;
+
+  // Struts are relative to the edges of the root window, which spans
+  // all monitors. Consists of the left, right, top and bottom widths,
+  // followed by the start and end of each along the edge.
+  let mut strut = [0; 12];
+  let (start_x, end_x) = (position.x, position.x + size.width - 1);
+  let (start_y, end_y) = (position.y, position.y + size.height - 1);
+
+  match edge {
+    DockEdge::Left => {
+      strut[0] = position.x + size.width;
+      (strut[4], strut[5]) = (start_y, end_y);
+    }
+    DockEdge::Right => {
+      strut[1] = root_width - position.x;
+      (strut[6], strut[7]) = (start_y, end_y);
+    }
+    DockEdge::Top => {
+      strut[2] = position.y + size.height;
+      (strut[8], strut[9]) = (start_x, end_x);
+    }
+    DockEdge::Bottom => {
+      strut[3] = root_height - position.y;
+      (strut[10], strut[11]) = (start_x, end_x);
+    }
+  }
+
+  let strut = strut.map(|value| value.max(0) as u32);
+  let atoms = DockAtoms::intern(&conn)?;
+
+  conn
+    .change_property32(
+      PropMode::REPLACE,
+      window_id,
+      atoms.strut_partial,
+      AtomEnum::CARDINAL,
+      &strut,
+    )?
+    .check()?;
+
+  // Older window managers only support `_NET_WM_STRUT`.
+  conn
+    .change_property32(
+      PropMode::REPLACE,
+      window_id,
+      atoms.strut,
+      AtomEnum::CARDINAL,
+      &strut[..4],
+    )?
+    .check()?;
+
+  info!("Set dock strut {:?} on X11 window {}.", strut, window_id);
+
+  Ok((size, position))
+}
+
+/// Removes the reserved screen space of the given X11 window. The dock
+/// window type is kept, such that the space can be reserved again
+/// without re-mapping the window (e.g. for auto-hiding widgets).
+///
+/// Errors if the window no longer exists.
+pub fn remove_dock_strut(window_id: u32) -> anyhow::Result<()> {
+  info!("Removing dock strut for X11 window {}.", window_id);
+
+  let (conn, _) = x11rb::connect(None)?;
+  let atoms = DockAtoms::intern(&conn)?;
+
+  for atom in [atoms.strut_partial, atoms.strut] {
+    conn.delete_property(window_id, atom)?.check()?;
+  }
+
+  conn.flush()?;
+
+  Ok(())
+}
@@ -0,0 +1,43 @@
+use std::{collections::HashMap, fs};
+
+use crate::common::{EdidInfo, parse_edid};
+
+/// Reads EDID info of connected displays from `/sys/class/drm`.
+///
+/// Returns a map of connector names (e.g. `DP-1`, `HDMI-A-1`) to their
+/// EDID info. Connectors without a readable EDID are skipped.
+pub fn read_drm_edids() -> HashMap<String, EdidInfo> {
+  let Ok(entries) = fs::read_dir("/sys/class/drm") else {
+    return HashMap::new();
+  };
+
+  entries
+    .filter_map(|entry| {
+      let entry = entry.ok()?;
+      let file_name = entry.file_name().to_string_lossy().to_string();
+
+      // Connector directories are named after their card, e.g.
+      // `card0-DP-1`.
+      let (_, connector) = file_name.split_once('-')?;
+
+      let status = fs::read_to_string(entry.path().join("status")).ok()?;
+      if status.trim() != "connected" {
+        return None;
+      }
+
+      let edid = fs::read(entry.path().join("edid")).ok()?;
+      Some((connector.to_string(), parse_edid(&edid)?))
+    })
+    .collect()
+}
+
+/// Whether a DRM connector name refers to the same output as a monitor
+/// name from the display server.
+///
+/// X11 drops the connector type suffix used by DRM, such that
+/// `HDMI-A-1` is reported as `HDMI-1`.
+pub fn is_same_connector(drm_name: &str, monitor_name: &str) -> bool {
+  let without_suffix = |name: &str| name.replace("-A-", "-").replace("-B-", "-");
+
+  drm_name == monitor_name || without_suffix(drm_name) == without_suffix(monitor_name)
+}
@@ -0,0 +1,9 @@
+mod dock_strut;
+mod drm;
+mod randr;
+mod window_ext_linux;
+
+pub use dock_strut::*;
+pub use drm::*;
+pub use randr::*;
+pub use window_ext_linux::*;
@@ -0,0 +1,129 @@
+use anyhow::{Context, bail};
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+use x11rb::{
+  connection::{Connection, RequestConnection},
+  protocol::{
+    Event,
+    randr::{self, ConnectionExt as _, NotifyMask},
+    xproto::{AtomEnum, ChangeWindowAttributesAux, ConnectionExt as _, EventMask},
+  },
+};
+
+use crate::monitor_state::WorkArea;
+
+/// Subscribes to screen changes on the X11 root window.
+///
+/// The returned receiver yields whenever a RandR screen, CRTC or output
+/// change is notified, or when `_NET_WORKAREA` is updated (e.g. a panel
+/// reserves space).
+///
+/// Errors if no X11 display or RandR extension is available, e.g. under
+/// Wayland.
+pub fn listen_screen_changes() -> anyhow::Result<mpsc::UnboundedReceiver<()>> {
+  let (conn, screen_num) = x11rb::connect(None)?;
+  let root = conn.setup().roots[screen_num].root;
+
+  conn
+    .extension_information(randr::X11_EXTENSION_NAME)?
+    .context("RandR extension is not available.")?;
+
+  // Output and CRTC change notifications require RandR 1.2.
+  conn.randr_query_version(1, 2)?.reply()?;
+
+  conn.randr_select_input(
+    root,
+    NotifyMask::SCREEN_CHANGE | NotifyMask::CRTC_CHANGE | NotifyMask::OUTPUT_CHANGE,
+  )?;
+
+  conn.change_window_attributes(
+    root,
+    &ChangeWindowAttributesAux::new().event_mask(EventMask::PROPERTY_CHANGE),
+  )?;
+
+  let work_area_atom = conn.intern_atom(false, b"_NET_WORKAREA")?.reply()?.atom;
+  conn.flush()?;
+
+  let (change_tx, change_rx) = mpsc::unbounded_channel();
+
+  // Blocks on X11 events, so it runs on a dedicated thread.
+  std::thread::spawn(move || {
+    loop {
+      let event = match conn.wait_for_event() {
+        Ok(event) => event,
+        Err(err) => {
+          warn!("Lost connection to X11 display: {}", err);
+          break;
+        }
+      };
+
+      let is_screen_change = match event {
+        Event::RandrScreenChangeNotify(_) | Event::RandrNotify(_) => true,
+        Event::PropertyNotify(event) => event.atom == work_area_atom,
+        _ => false,
+      };
+
+      if is_screen_change && change_tx.send(()).is_err() {
+        break;
+      }
+    }
+  });
+
+  info!("Listening for RandR screen changes.");
+
+  Ok(change_rx)
+}
+
+/// Reads the work area of the current desktop from `_NET_WORKAREA`.
+///
+/// The work area spans all monitors and excludes space reserved by
+/// panels and docks. Errors if no EWMH window manager has set it.
+pub fn read_work_area() -> anyhow::Result<WorkArea> {
+  let (conn, screen_num) = x11rb::connect(None)?;
+  let root = conn
+    .setup()
+    .roots
+    .get(screen_num)
+    .context("No X11 screen available.")?
+    .root;
+
+  let current_desktop_cookie = conn.intern_atom(false, b"_NET_CURRENT_DESKTOP")?;
+  let work_area_cookie = conn.intern_atom(false, b"_NET_WORKAREA")?;
+  let current_desktop_atom = current_desktop_cookie.reply()?.atom;
+  let work_area_atom = work_area_cookie.reply()?.atom;
+
+  let current_desktop = conn
+    .get_property(false, root, current_desktop_atom, AtomEnum::CARDINAL, 0, 1)?
+    .reply()?
+    .value32()
+    .and_then(|mut values| values.next())
+    .unwrap_or(0);
+
+  // Consists of the x, y, width and height for each desktop.
+  let work_area = conn
+    .get_property(
+      false,
+      root,
+      work_area_atom,
+      AtomEnum::CARDINAL,
+      current_desktop * 4,
+      4,
+    )?
+    .reply()?;
+
+  let values = work_area
+    .value32()
+    .context("Invalid format of _NET_WORKAREA.")?
+    .collect::<Vec<_>>();
+
+  let [x, y, width, height] = values[..] else {
+    bail!("No work area is set for the current desktop.");
+  };
+
+  Ok(WorkArea {
+    x: x as i32,
+    y: y as i32,
+    width,
+    height,
+  })
+}
@@ -0,0 +1,55 @@
+use anyhow::bail;
+use raw_window_handle::{HasWindowHandle, RawWindowHandle};
+use tauri::{PhysicalPosition, PhysicalSize, Runtime, Window};
+
+use super::dock_strut;
+use crate::config::DockEdge;
+
+pub trait WindowExtLinux {
+  /// Gets the X11 window ID. Errors if the window isn't an X11 window
+  /// (e.g. under Wayland).
+  fn x11_window_id(&self) -> anyhow::Result<u32>;
+
+  /// Marks the window as a dock window. Re-maps the window if already
+  /// shown, so this should only be called once per window.
+  fn set_dock_window_type(&self) -> anyhow::Result<()>;
+
+  fn allocate_dock_strut(
+    &self,
+    size: PhysicalSize<i32>,
+    position: PhysicalPosition<i32>,
+    edge: DockEdge,
+  ) -> anyhow::Result<(PhysicalSize<i32>, PhysicalPosition<i32>)>;
+}
+
+impl<R: Runtime> WindowExtLinux for Window<R> {
+  fn x11_window_id(&self) -> anyhow::Result<u32> {
+    match self.window_handle()?.as_raw() {
+      RawWindowHandle::Xlib(handle) => Ok(handle.window as u32),
+      RawWindowHandle::Xcb(handle) => Ok(handle.window.get()),
+      _ => bail!("Window is not an X11 window."),
+    }
+  }
+
+  fn set_dock_window_type(&self) -> anyhow::Result<()> {
+    dock_strut::set_dock_window_type(self.x11_window_id()?)?;
+
+    // Window managers generally only read the window type when a window
+    // is mapped, so the window is re-mapped if already shown.
+    if self.is_visible()? {
+      self.hide()?;
+      self.show()?;
+    }
+
+    Ok(())
+  }
+
+  fn allocate_dock_strut(
+    &self,
+    size: PhysicalSize<i32>,
+    position: PhysicalPosition<i32>,
+    edge: DockEdge,
+  ) -> anyhow::Result<(PhysicalSize<i32>, PhysicalPosition<i32>)> {
+    dock_strut::create_dock_strut(self.x11_window_id()?, size, position, edge)
+  }
+}
@@ -1,8 +1,25 @@
+// EDID info is only read on Linux so far.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+mod edid;
 mod fs_util;
+mod interpolation;
+mod json_merge;
+mod jsonc;
 mod length_value;
+#[cfg(target_os = "linux")]
+pub mod linux;
 pub mod path_ext;
+#[cfg(test)]
+mod test_util;
+#[cfg(target_os = "windows")]
 pub mod windows;
 
+pub use edid::*;
 pub use fs_util::*;
+pub use interpolation::*;
+pub use json_merge::*;
+pub use jsonc::*;
 pub use length_value::*;
 pub use path_ext::*;
+#[cfg(test)]
+pub use test_util::*;
@@ -0,0 +1,10 @@
+use std::{fs, path::PathBuf};
+
+use super::PathExt;
+
+/// Creates an empty directory under the system temp directory.
+pub fn temp_dir() -> PathBuf {
+  let dir = std::env::temp_dir().join(format!("edgebar-test-{}", uuid::Uuid::new_v4()));
+  fs::create_dir_all(&dir).unwrap();
+  dir.to_absolute().unwrap()
+}
@@ -1,4 +1,11 @@
-use std::mem::transmute;
+use std::{
+  collections::HashMap,
+  mem::transmute,
+  sync::{
+    atomic::{AtomicU32, Ordering},
+    LazyLock, Mutex,
+  },
+};
 
 use anyhow::bail;
 use tauri::{PhysicalPosition, PhysicalSize};
@@ -19,13 +26,33 @@ use windows::Win32::{
 
 use crate::config::DockEdge;
 
-// Store the original window procedure
-static mut PREV_WND_PROC: Option<WNDPROC> = None;
+/// Registered app bars by window handle. This is necessary because the
+/// window proc doesn't have access to the original window procedure or
+/// the edge directly.
+static APP_BARS: LazyLock<Mutex<HashMap<isize, AppBar>>> =
+  LazyLock::new(|| Mutex::new(HashMap::new()));
 
-// Define a global variable to store the edge.  This is necessary because
-// the window proc doesn't have access to the edge directly.
-static mut DOCK_EDGE: Option<DockEdge> = None;
-static mut CALLBACK_MESSAGE: u32 = 0;
+static CALLBACK_MESSAGE: AtomicU32 = AtomicU32::new(0);
+
+#[derive(Clone)]
+struct AppBar {
+  /// Original window procedure of the subclassed window.
+  prev_wnd_proc: WNDPROC,
+  edge: DockEdge,
+}
+
+/// Gets the registered app bar for the given window handle.
+fn app_bar(hwnd: HWND) -> Option<AppBar> {
+  APP_BARS.lock().unwrap().get(&(hwnd.0 as isize)).cloned()
+}
+
+/// Calls the original window procedure of the given window.
+unsafe fn call_prev_wnd_proc(hwnd: HWND, msg: u32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+  match app_bar(hwnd) {
+    Some(app_bar) => unsafe { CallWindowProcA(app_bar.prev_wnd_proc, hwnd, msg, wparam, lparam) },
+    None => unsafe { DefWindowProcA(hwnd, msg, wparam, lparam) },
+  }
+}
 
 // New window procedure to handle ABN_POSCHANGED
 unsafe extern "system" fn new_wnd_proc(
@@ -34,41 +61,18 @@ unsafe extern "system" fn new_wnd_proc(
   wparam: WPARAM,
   lparam: LPARAM,
 ) -> LRESULT {
-  match msg {
-    msg if msg == unsafe { CALLBACK_MESSAGE } => {
-      if wparam.0 == ABN_POSCHANGED as usize {
-        info!("Received ABN_POSCHANGED message");
-        // Call our function to update the appbar position
-        #[allow(static_mut_refs)]
-        let edge = unsafe { DOCK_EDGE.clone() };
-        if let Some(edge) = edge {
-          if let Err(e) = update_app_bar_position(hwnd, Some(edge)) {
-            eprintln!("Error updating app bar position: {}", e);
-          }
-        }
+  if msg == CALLBACK_MESSAGE.load(Ordering::Relaxed) && wparam.0 == ABN_POSCHANGED as usize {
+    info!("Received ABN_POSCHANGED message");
+    // Call our function to update the appbar position
+    if let Some(app_bar) = app_bar(hwnd) {
+      if let Err(e) = update_app_bar_position(hwnd, Some(app_bar.edge)) {
+        eprintln!("Error updating app bar position: {}", e);
       }
-      // Call the original window procedure to ensure default processing
-      let result = unsafe {
-        if let Some(prev_wnd_proc) = PREV_WND_PROC {
-          CallWindowProcA(prev_wnd_proc, hwnd, msg, wparam, lparam)
-        } else {
-          DefWindowProcA(hwnd, msg, wparam, lparam)
-        }
-      };
-      result
-    }
-    _ => {
-      // For other messages, call the original window procedure
-      let result = unsafe {
-        if let Some(prev_wnd_proc) = PREV_WND_PROC {
-          CallWindowProcA(prev_wnd_proc, hwnd, msg, wparam, lparam)
-        } else {
-          DefWindowProcA(hwnd, msg, wparam, lparam)
-        }
-      };
-      result
     }
   }
+
+  // Call the original window procedure to ensure default processing
+  unsafe { call_prev_wnd_proc(hwnd, msg, wparam, lparam) }
 }
 
 pub fn update_app_bar_position(hwnd: HWND, edge: Option<DockEdge>) -> anyhow::Result<()> {
@@ -87,7 +91,7 @@ pub fn update_app_bar_position(hwnd: HWND, edge: Option<DockEdge>) -> anyhow::Re
   let mut data = APPBARDATA {
     cbSize: std::mem::size_of::<APPBARDATA>() as u32,
     hWnd: hwnd,
-    uCallbackMessage: unsafe { CALLBACK_MESSAGE },
+    uCallbackMessage: CALLBACK_MESSAGE.load(Ordering::Relaxed),
     uEdge: match edge {
       DockEdge::Left => ABE_LEFT,
       DockEdge::Top => ABE_TOP,
@@ -136,17 +140,24 @@ pub fn create_app_bar(
 
   info!("Creating app bar with initial rect: {:?}", rect);
 
-  unsafe {
-    CALLBACK_MESSAGE = RegisterWindowMessageA(windows::core::s!("AppBarMessage"));
-    if CALLBACK_MESSAGE == 0 {
-      bail!("Failed to register window message: {:?}", GetLastError());
-    }
+  let callback_message = unsafe { RegisterWindowMessageA(windows::core::s!("AppBarMessage")) };
+
+  if callback_message == 0 {
+    bail!("Failed to register window message: {:?}", GetLastError());
+  }
+
+  CALLBACK_MESSAGE.store(callback_message, Ordering::Relaxed);
+
+  // Deallocate any previous app bar of the window (e.g. when it's
+  // re-docked), such that it isn't subclassed twice.
+  if APP_BARS.lock().unwrap().contains_key(&window_handle) {
+    remove_app_bar(window_handle)?;
   }
 
   let mut data = APPBARDATA {
     cbSize: std::mem::size_of::<APPBARDATA>() as u32,
     hWnd: HWND(window_handle as _),
-    uCallbackMessage: unsafe { CALLBACK_MESSAGE },
+    uCallbackMessage: CALLBACK_MESSAGE.load(Ordering::Relaxed),
     uEdge: match edge {
       DockEdge::Left => ABE_LEFT,
       DockEdge::Top => ABE_TOP,
@@ -161,22 +172,25 @@ pub fn create_app_bar(
     bail!("Failed to register new app bar.");
   }
 
-  // Store the edge globally
-  unsafe {
-    DOCK_EDGE = Some(edge);
-  }
-
   // Subclass the window to receive messages. Store the original window
-  // proc.
-  unsafe {
+  // proc and the edge.
+  {
+    let mut app_bars = APP_BARS.lock().unwrap();
     let hwnd = HWND(window_handle as _);
-    let prev_wnd_proc = SetWindowLongPtrA(hwnd, GWLP_WNDPROC, new_wnd_proc as *const () as isize);
-    if prev_wnd_proc != 0 {
-      PREV_WND_PROC = Some(transmute(prev_wnd_proc));
-    } else {
-      let error = GetLastError();
-      bail!("SetWindowLongPtrA failed: {:?}", error);
+    let prev_wnd_proc =
+      unsafe { SetWindowLongPtrA(hwnd, GWLP_WNDPROC, new_wnd_proc as *const () as isize) };
+
+    if prev_wnd_proc == 0 {
+      bail!("SetWindowLongPtrA failed: {:?}", unsafe { GetLastError() });
     }
+
+    app_bars.insert(
+      window_handle,
+      AppBar {
+        prev_wnd_proc: unsafe { transmute(prev_wnd_proc) },
+        edge,
+      },
+    );
   }
 
   // Query to get the adjusted position.
@@ -226,14 +240,18 @@ pub fn create_app_bar(
 pub fn remove_app_bar(handle: isize) -> anyhow::Result<()> {
   info!("Removing app bar for {:?}.", handle);
 
-  unsafe {
-    let hwnd = HWND(handle as _);
-    // Restore the original window procedure
-    if let Some(prev_wnd_proc) = PREV_WND_PROC {
-      SetWindowLongPtrA(hwnd, GWLP_WNDPROC, transmute(prev_wnd_proc));
-      PREV_WND_PROC = None; // Clear the global variable
+  // Remove the entry before restoring the original window procedure,
+  // such that the lock isn't held across the Win32 calls.
+  let app_bar = APP_BARS.lock().unwrap().remove(&handle);
+
+  if let Some(app_bar) = app_bar {
+    unsafe {
+      SetWindowLongPtrA(
+        HWND(handle as _),
+        GWLP_WNDPROC,
+        transmute(app_bar.prev_wnd_proc),
+      );
     }
-    DOCK_EDGE = None;
   }
 
   let mut abd = APPBARDATA {
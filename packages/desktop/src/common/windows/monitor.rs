@@ -0,0 +1,31 @@
+use anyhow::bail;
+use windows::Win32::{
+  Foundation::POINT,
+  Graphics::Gdi::{GetMonitorInfoW, MonitorFromPoint, MONITORINFO, MONITOR_DEFAULTTONEAREST},
+};
+
+use crate::monitor_state::WorkArea;
+
+/// Gets the work area of the monitor at the given point, i.e. its bounds
+/// minus the taskbar and any registered app bars.
+pub fn monitor_work_area(x: i32, y: i32) -> anyhow::Result<WorkArea> {
+  let monitor = unsafe { MonitorFromPoint(POINT { x, y }, MONITOR_DEFAULTTONEAREST) };
+
+  let mut monitor_info = MONITORINFO {
+    cbSize: std::mem::size_of::<MONITORINFO>() as u32,
+    ..Default::default()
+  };
+
+  if !unsafe { GetMonitorInfoW(monitor, &mut monitor_info) }.as_bool() {
+    bail!("Failed to get monitor info at ({}, {}).", x, y);
+  }
+
+  let rect = monitor_info.rcWork;
+
+  Ok(WorkArea {
+    x: rect.left,
+    y: rect.top,
+    width: (rect.right - rect.left) as u32,
+    height: (rect.bottom - rect.top) as u32,
+  })
+}
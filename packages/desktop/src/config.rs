@@ -1,18 +1,64 @@
 use std::{
   collections::HashMap,
   fs::{self},
-  path::PathBuf,
+  path::{Path, PathBuf},
   sync::Arc,
+  time::Duration,
 };
 
-use anyhow::Context;
+use anyhow::{bail, Context};
 use clap::ValueEnum;
-use serde::{Deserialize, Deserializer, Serialize};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use tauri::{path::BaseDirectory, AppHandle, Manager};
-use tokio::sync::{broadcast, Mutex};
-use tracing::{error, info};
+use tokio::{
+  sync::{broadcast, Mutex},
+  task,
+};
+use tracing::{error, info, warn};
+
+use crate::{
+  asset_server::DEFAULT_ASSET_SERVER_PORT,
+  common::{
+    content_hash, copy_dir_all, deep_merge, has_extension, interpolate_json, map_json_strings,
+    read_and_parse_config, read_content_hash, replace_variable, restore_templates, strip_inherited,
+    write_config, ConfigFormat, LengthValue, PathExt, ValueEscape,
+  },
+  config_migrations::{read_and_migrate, read_migrated, ConfigKind, CONFIG_VERSION},
+};
 
-use crate::common::{copy_dir_all, has_extension, read_and_parse_json, LengthValue, PathExt};
+/// Interval to poll the settings file for changes made on disk at.
+const SETTINGS_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Fields of a widget config that variables are interpolated in (see
+/// `interpolate_json`).
+const INTERPOLATED_FIELDS: &[&str] = &[
+  "htmlPath",
+  "privileges/shellCommands/*/program",
+  "presets/*/offsetX",
+  "presets/*/offsetY",
+  "presets/*/width",
+  "presets/*/height",
+  "presets/*/dockToEdge/windowMargin",
+  "presets/*/dockToEdge/autoHide/revealStrip",
+  "presets/*/overrides/*/offsetX",
+  "presets/*/overrides/*/offsetY",
+  "presets/*/overrides/*/width",
+  "presets/*/overrides/*/height",
+  "presets/*/overrides/*/dockToEdge/windowMargin",
+  "presets/*/overrides/*/dockToEdge/autoHide/revealStrip",
+];
+
+/// Fields of a widget config that are regular expressions. Variable
+/// values are escaped when interpolated in these, such that they are
+/// matched literally.
+const INTERPOLATED_REGEX_FIELDS: &[&str] = &["privileges/shellCommands/*/argsRegex"];
+
+/// Interpolated fields and how variable values are escaped in them.
+const INTERPOLATED_FIELD_SETS: [(&[&str], ValueEscape); 2] = [
+  (INTERPOLATED_FIELDS, ValueEscape::Raw),
+  (INTERPOLATED_REGEX_FIELDS, ValueEscape::Regex),
+];
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -21,46 +67,136 @@ pub struct SettingsConfig {
   #[serde(rename = "$schema")]
   schema: Option<String>,
 
+  /// Schema version of the settings file.
+  #[serde(default = "default_config_version")]
+  pub version: u32,
+
   /// Widget configs to be launched on startup.
   pub startup_configs: Vec<StartupConfig>,
+
+  /// User-defined variables that can be referenced in widget configs as
+  /// `${name}`.
+  #[serde(default)]
+  pub variables: HashMap<String, String>,
+
+  /// Runtime options for EdgeBar.
+  #[serde(default)]
+  pub settings: RuntimeSettings,
+
+  /// Named sets of startup configs and variable overrides that can be
+  /// switched between at runtime.
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub profiles: Vec<ProfileConfig>,
+
+  /// Name of the currently active profile, if any. Persisted when
+  /// switching profiles, such that it is restored on the next launch.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub active_profile: Option<String>,
+}
+
+impl SettingsConfig {
+  /// Returns the profile with the given name.
+  pub fn profile(&self, name: &str) -> Option<&ProfileConfig> {
+    self.profiles.iter().find(|profile| profile.name == name)
+  }
+
+  /// Returns the active profile, if it exists.
+  pub fn active_profile(&self) -> Option<&ProfileConfig> {
+    self
+      .active_profile
+      .as_deref()
+      .and_then(|name| self.profile(name))
+  }
+
+  /// Returns the user-defined variables with overrides from the given
+  /// profile applied.
+  pub fn variables_for(&self, profile_name: Option<&str>) -> HashMap<String, String> {
+    let mut variables = self.variables.clone();
+
+    if let Some(profile) = profile_name.and_then(|name| self.profile(name)) {
+      variables.extend(profile.variables.clone());
+    }
+
+    variables
+  }
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
-pub struct StartupConfig {
-  /// Relative path to widget configs to launch on startup.
-  pub path: PathBuf,
+pub struct ProfileConfig {
+  /// Unique name of the profile (e.g. `presentation`).
+  pub name: String,
 
-  /// Preset name within the widget config.
-  pub preset: String,
+  /// Widget configs to launch when the profile is active.
+  pub startup_configs: Vec<StartupConfig>,
+
+  /// Variables that override the top-level `variables` when the profile
+  /// is active.
+  #[serde(default)]
+  pub variables: HashMap<String, String>,
 }
 
-// Deserializer that handles `StartupConfig` objects and string format from
-// v2.3.0 and earlier.
-impl<'de> Deserialize<'de> for StartupConfig {
-  fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-  where
-    D: Deserializer<'de>,
-  {
-    #[derive(Deserialize)]
-    #[serde(untagged)]
-    enum StringOrObject {
-      String(String),
-      Object { path: PathBuf, preset: String },
-    }
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct RuntimeSettings {
+  /// Port for the localhost asset server. Changes require a restart.
+  pub asset_server_port: u16,
 
-    let value = StringOrObject::deserialize(deserializer)?;
+  /// Interval to poll for monitor changes at (in milliseconds).
+  pub monitor_poll_interval_ms: u64,
 
-    Ok(match value {
-      StringOrObject::String(s) => StartupConfig {
-        path: PathBuf::from(s),
-        preset: "default".to_string(),
-      },
-      StringOrObject::Object { path, preset } => StartupConfig { path, preset },
-    })
+  /// Log filter directives (e.g. `debug` or `edgebar=trace`). The
+  /// `LOG_LEVEL` environment variable takes precedence if set.
+  pub log_level: Option<String>,
+
+  /// Directory to store webview data (e.g. `localStorage` and cache) in.
+  /// Relative paths are resolved from the config directory. Defaults to
+  /// `~/.edgebar`.
+  pub webview_data_dir: Option<PathBuf>,
+
+  /// Substrings of window titles to ignore in the window provider.
+  pub ignored_window_titles: Vec<String>,
+
+  /// Whether to reopen the widgets that were open when EdgeBar last
+  /// exited, instead of the widgets in `startupConfigs`.
+  pub restore_session: bool,
+
+  /// User-defined names for monitors, matched by their EDID identity.
+  /// Can be used in a `monitorSelection` of type `alias`.
+  pub monitor_aliases: HashMap<String, MonitorMatch>,
+}
+
+impl RuntimeSettings {
+  /// Returns the interval to poll for monitor changes at.
+  pub fn monitor_poll_interval(&self) -> Duration {
+    Duration::from_millis(self.monitor_poll_interval_ms)
+  }
+}
+
+impl Default for RuntimeSettings {
+  fn default() -> Self {
+    Self {
+      asset_server_port: DEFAULT_ASSET_SERVER_PORT,
+      monitor_poll_interval_ms: 4000,
+      log_level: None,
+      webview_data_dir: None,
+      ignored_window_titles: default_ignored_titles(),
+      restore_session: false,
+      monitor_aliases: HashMap::new(),
+    }
   }
 }
 
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartupConfig {
+  /// Relative path to widget configs to launch on startup.
+  pub path: PathBuf,
+
+  /// Preset name within the widget config.
+  pub preset: String,
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct WidgetConfig {
@@ -68,6 +204,14 @@ pub struct WidgetConfig {
   #[serde(rename = "$schema")]
   schema: Option<String>,
 
+  /// Schema version of the widget config file.
+  #[serde(default = "default_config_version")]
+  pub version: u32,
+
+  /// Relative path to a base widget config to inherit fields from.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub extends: Option<PathBuf>,
+
   /// Relative path to entry point HTML file.
   pub html_path: PathBuf,
 
@@ -86,6 +230,11 @@ pub struct WidgetConfig {
   /// Whether the Tauri window frame should be transparent.
   pub transparent: bool,
 
+  /// Whether to remember where the user moves or resizes the widget to.
+  /// Does not apply to widgets that are docked to an edge.
+  #[serde(default = "default_bool::<false>")]
+  pub persist_placement: bool,
+
   /// How network requests should be cached.
   #[serde(default)]
   pub caching: WidgetCaching,
@@ -94,9 +243,7 @@ pub struct WidgetConfig {
   #[serde(default)]
   pub privileges: WidgetPrivileges,
 
-  /// Where to place the widget. Add alias for `defaultPlacements` for
-  /// compatibility with v2.3.0 and earlier.
-  #[serde(alias = "defaultPlacements")]
+  /// Where to place the widget.
   pub presets: Vec<WidgetPreset>,
 }
 
@@ -143,6 +290,23 @@ pub struct WidgetPreset {
   #[serde(default = "default_preset_name")]
   pub name: String,
 
+  /// Explicit widget ID for the preset's windows (e.g. `clock` gives
+  /// `widget-clock`). Derived from the config path and preset name if
+  /// not set.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub id: Option<String>,
+
+  /// Name of another preset within the same widget config to inherit
+  /// fields from.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub extends: Option<String>,
+
+  /// Whether to hide the preset's windows while a fullscreen window
+  /// (e.g. a video player or game) is in the foreground on the same
+  /// monitor.
+  #[serde(default = "default_bool::<false>")]
+  pub hide_on_fullscreen: bool,
+
   #[serde(flatten)]
   pub placement: WidgetPlacement,
 }
@@ -171,6 +335,67 @@ pub struct WidgetPlacement {
   /// How to reserve space for the widget.
   #[serde(default)]
   pub dock_to_edge: DockConfig,
+
+  /// Overrides of the placement for specific monitors. Overrides are
+  /// applied in order, such that later overrides take precedence.
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub overrides: Vec<PlacementOverride>,
+}
+
+/// Placement fields to override on monitors matched by
+/// `monitor_selection`. Unset fields are kept from the base placement.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlacementOverride {
+  /// Monitor(s) to apply the override on.
+  pub monitor_selection: MonitorSelection,
+
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub anchor: Option<AnchorPoint>,
+
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub offset_x: Option<LengthValue>,
+
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub offset_y: Option<LengthValue>,
+
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub width: Option<LengthValue>,
+
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub height: Option<LengthValue>,
+
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub dock_to_edge: Option<DockConfig>,
+}
+
+impl PlacementOverride {
+  /// Applies the overridden fields to the given placement.
+  pub fn apply(&self, placement: &mut WidgetPlacement) {
+    if let Some(anchor) = self.anchor {
+      placement.anchor = anchor;
+    }
+
+    if let Some(offset_x) = &self.offset_x {
+      placement.offset_x = offset_x.clone();
+    }
+
+    if let Some(offset_y) = &self.offset_y {
+      placement.offset_y = offset_y.clone();
+    }
+
+    if let Some(width) = &self.width {
+      placement.width = width.clone();
+    }
+
+    if let Some(height) = &self.height {
+      placement.height = height.clone();
+    }
+
+    if let Some(dock_to_edge) = &self.dock_to_edge {
+      placement.dock_to_edge = dock_to_edge.clone();
+    }
+  }
 }
 
 #[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize, ValueEnum)]
@@ -196,6 +421,24 @@ pub enum MonitorSelection {
   Secondary,
   Index(usize),
   Name(String),
+  Edid(MonitorMatch),
+  Alias(String),
+}
+
+/// Matches a monitor by its EDID identity. All specified fields need to
+/// match, and a match without any fields matches no monitors.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitorMatch {
+  /// Three-letter manufacturer ID (e.g. `DEL`).
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub manufacturer: Option<String>,
+
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub model: Option<String>,
+
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub serial: Option<String>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Default)]
@@ -203,6 +446,11 @@ pub enum MonitorSelection {
 pub struct WidgetPrivileges {
   /// Shell commands that the widget is allowed to run.
   pub shell_commands: Vec<ShellPrivilege>,
+
+  /// Message bus topics that the widget is allowed to publish and
+  /// subscribe to. A trailing `*` matches any suffix (e.g. `calendar/*`).
+  #[serde(default)]
+  pub topics: Vec<String>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Default)]
@@ -226,10 +474,50 @@ pub struct DockConfig {
   /// Edge to dock the widget to.
   pub edge: Option<DockEdge>,
 
+  /// Position among other widgets docked to the same monitor edge.
+  /// Widgets with a lower order are docked closer to the edge. Defaults
+  /// to `0`, with ties stacked in the order the widgets were opened.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub order: Option<i32>,
+
   /// Margin to reserve after the widget window. Can be positive or
   /// negative.
   #[serde(default)]
   pub window_margin: LengthValue,
+
+  /// Whether to slide the widget out of view when not in use.
+  #[serde(default)]
+  pub auto_hide: AutoHideConfig,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AutoHideConfig {
+  /// Whether to hide the docked widget when the cursor leaves it. The
+  /// widget is revealed again when the cursor touches the monitor edge.
+  pub enabled: bool,
+
+  /// Length of the widget that stays on-screen while hidden.
+  pub reveal_strip: LengthValue,
+
+  /// Delay before hiding the widget after the cursor leaves it.
+  pub hide_delay_ms: u64,
+}
+
+impl AutoHideConfig {
+  pub fn hide_delay(&self) -> Duration {
+    Duration::from_millis(self.hide_delay_ms)
+  }
+}
+
+impl Default for AutoHideConfig {
+  fn default() -> Self {
+    Self {
+      enabled: false,
+      reveal_strip: LengthValue::from_px(2.),
+      hide_delay_ms: 500,
+    }
+  }
 }
 
 #[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq)]
@@ -247,20 +535,43 @@ impl DockEdge {
   }
 }
 
+/// Un-interpolated source of a widget config as last read from or
+/// written to disk.
+#[derive(Clone, Debug)]
+struct WidgetConfigSource {
+  /// Raw JSON value of the config file.
+  value: Value,
+
+  /// Hash of the file content. Used to detect external modifications.
+  content_hash: u64,
+
+  /// Hashes of the file contents of base configs inherited through
+  /// `extends`, keyed by their paths.
+  base_hashes: HashMap<PathBuf, u64>,
+}
+
 #[derive(Debug)]
 pub struct Config {
   /// Handle to the Tauri application.
+  // Only read by the Windows-only menu commands so far.
+  #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
   app_handle: AppHandle,
 
   /// Directory where config files are stored.
   pub config_dir: PathBuf,
 
+  /// Path to the global settings file.
+  settings_path: PathBuf,
+
   /// Global settings.
   pub settings: Arc<Mutex<SettingsConfig>>,
 
   /// List of widget configs.
   pub widget_configs: Arc<Mutex<HashMap<PathBuf, WidgetConfig>>>,
 
+  /// Widget configs as read from disk, prior to variable interpolation.
+  widget_config_sources: Arc<Mutex<HashMap<PathBuf, WidgetConfigSource>>>,
+
   _settings_change_rx: broadcast::Receiver<SettingsConfig>,
 
   pub settings_change_tx: broadcast::Sender<SettingsConfig>,
@@ -272,6 +583,7 @@ pub struct Config {
 
 impl Config {
   // Method to retrieve AppHandle
+  #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
   pub fn app_handle(&self) -> &AppHandle {
     &self.app_handle
   }
@@ -288,17 +600,23 @@ impl Config {
         .context("Unable to get home directory.")?,
     };
 
-    let settings = Self::read_settings_or_init(app_handle, &config_dir)?;
-    let widget_configs = Self::read_widget_configs(&config_dir)?;
+    let (settings_path, settings) = Self::read_settings_or_init(app_handle, &config_dir)?;
+    let config_dir = config_dir.to_absolute()?;
+
+    let variables = settings.variables_for(settings.active_profile.as_deref());
+    let (widget_configs, widget_config_sources) =
+      Self::read_widget_configs(&config_dir, &variables)?;
 
     let (settings_change_tx, _settings_change_rx) = broadcast::channel(16);
     let (widget_configs_change_tx, _widget_configs_change_rx) = broadcast::channel(16);
 
     Ok(Self {
       app_handle: app_handle.clone(),
-      config_dir: config_dir.to_absolute()?,
+      config_dir,
+      settings_path,
       settings: Arc::new(Mutex::new(settings)),
       widget_configs: Arc::new(Mutex::new(widget_configs)),
+      widget_config_sources: Arc::new(Mutex::new(widget_config_sources)),
       _settings_change_rx,
       settings_change_tx,
       _widget_configs_change_rx,
@@ -308,11 +626,12 @@ impl Config {
 
   /// Reads the global settings file or initializes it with the starter.
   ///
-  /// Returns the parsed `SettingsConfig`.
+  /// Returns the path to the settings file and the parsed
+  /// `SettingsConfig`.
   fn read_settings_or_init(
     app_handle: &AppHandle,
     dir: &PathBuf,
-  ) -> anyhow::Result<SettingsConfig> {
+  ) -> anyhow::Result<(PathBuf, SettingsConfig)> {
     let settings = Self::read_settings(&dir)?;
 
     match settings {
@@ -325,23 +644,51 @@ impl Config {
     }
   }
 
-  /// Reads the global settings file.
+  /// Reads the global settings file and upgrades it to the current
+  /// schema version.
+  ///
+  /// The settings file can be either `settings.json`, `settings.jsonc`, or
+  /// `settings.toml`.
   ///
-  /// Returns the parsed `SettingsConfig` if found.
-  fn read_settings(dir: &PathBuf) -> anyhow::Result<Option<SettingsConfig>> {
-    let settings_path = dir.join("settings.json");
+  /// Returns the path to the settings file and the parsed
+  /// `SettingsConfig` if found.
+  fn read_settings(dir: &PathBuf) -> anyhow::Result<Option<(PathBuf, SettingsConfig)>> {
+    let settings_path = ConfigFormat::ALL
+      .iter()
+      .map(|format| dir.join(format!("settings.{}", format.extension())))
+      .find(|path| path.exists());
+
+    match settings_path {
+      None => Ok(None),
+      Some(settings_path) => {
+        let (value, _) = read_and_migrate(&settings_path, ConfigKind::Settings)?;
+
+        let mut settings = serde_json::from_value::<SettingsConfig>(value)
+          .with_context(|| format!("Failed to parse settings at '{}'.", settings_path.display()))?;
+
+        // Ignore the active profile if it no longer exists.
+        if settings.active_profile.is_some() && settings.active_profile().is_none() {
+          let name = settings.active_profile.take().unwrap_or_default();
+          warn!("Active profile '{}' does not exist in settings.", name);
+        }
 
-    match settings_path.exists() {
-      false => Ok(None),
-      true => read_and_parse_json(&settings_path),
+        Ok(Some((settings_path, settings)))
+      }
     }
   }
 
   /// Aggregates all valid widget configs at the 2nd-level of the given
-  /// directory (i.e. `<CONFIG_DIR>/*/*.edgebar.json`).
+  /// directory (i.e. `<CONFIG_DIR>/*/*.edgebar.{json,jsonc,toml}`).
   ///
-  /// Returns a hashmap of config paths to their `WidgetConfig` instances.
-  fn read_widget_configs(dir: &PathBuf) -> anyhow::Result<HashMap<PathBuf, WidgetConfig>> {
+  /// Returns hashmaps of config paths to their `WidgetConfig` instances
+  /// and to their un-interpolated JSON sources.
+  fn read_widget_configs(
+    dir: &PathBuf,
+    variables: &HashMap<String, String>,
+  ) -> anyhow::Result<(
+    HashMap<PathBuf, WidgetConfig>,
+    HashMap<PathBuf, WidgetConfigSource>,
+  )> {
     let dir_paths = fs::read_dir(dir)
       .with_context(|| format!("Failed to read directory: {}", dir.display()))?
       .filter_map(|entry| Some(entry.ok()?.path()));
@@ -355,17 +702,19 @@ impl Config {
 
     // Collect the found config files.
     let config_paths = subdir_paths
-      .filter(|path| path.is_file() && has_extension(&path, ".edgebar.json"))
+      .filter(|path| path.is_file() && Self::is_widget_config_path(path))
       .collect::<Vec<PathBuf>>();
 
     let mut configs = HashMap::new();
+    let mut sources = HashMap::new();
 
     // Parse the found config files.
     for path in config_paths {
-      match Self::parse_widget_config(&path) {
-        Ok((config_path, config)) => {
+      match Self::parse_widget_config(&path, dir, variables) {
+        Ok((config_path, config, source)) => {
           info!("Found valid widget config at: {}", config_path.display());
-          configs.insert(config_path, config);
+          configs.insert(config_path.clone(), config);
+          sources.insert(config_path, source);
         }
         Err(err) => {
           error!("{:?}", err);
@@ -373,15 +722,57 @@ impl Config {
       }
     }
 
-    Ok(configs)
+    Ok((configs, sources))
   }
 
-  fn parse_widget_config(config_path: &PathBuf) -> anyhow::Result<(PathBuf, WidgetConfig)> {
+  /// Returns whether the path has a widget config extension (e.g.
+  /// `.edgebar.json`).
+  fn is_widget_config_path(path: &PathBuf) -> bool {
+    ConfigFormat::ALL
+      .iter()
+      .any(|format| has_extension(path, &format!(".edgebar.{}", format.extension())))
+  }
+
+  /// Reads a widget config and interpolates variables within its path,
+  /// shell privilege and length fields.
+  ///
+  /// Returns the absolute config path, the interpolated `WidgetConfig`,
+  /// and the un-interpolated JSON source.
+  fn parse_widget_config(
+    config_path: &PathBuf,
+    config_dir: &PathBuf,
+    variables: &HashMap<String, String>,
+  ) -> anyhow::Result<(PathBuf, WidgetConfig, WidgetConfigSource)> {
     let abs_path = config_path
       .to_absolute()
       .with_context(|| format!("Invalid widget config path '{}'.", config_path.display()))?;
 
-    let config = read_and_parse_json::<WidgetConfig>(&abs_path).map_err(|err| {
+    let mut base_hashes = HashMap::new();
+
+    let parse_res =
+      read_and_migrate(&abs_path, ConfigKind::WidgetConfig).and_then(|(source, hash)| {
+        let resolved = Self::resolve_config_extends(
+          &abs_path,
+          &source,
+          &mut vec![abs_path.clone()],
+          &mut base_hashes,
+        )?;
+        let resolved = Self::resolve_preset_extends(resolved)?;
+        let variables = Self::interpolation_variables(config_dir, &abs_path, variables);
+        let mut interpolated = resolved;
+
+        for (fields, escape) in INTERPOLATED_FIELD_SETS {
+          interpolated = interpolate_json(&interpolated, fields, &variables, escape)?;
+        }
+
+        Ok((
+          serde_json::from_value::<WidgetConfig>(interpolated)?,
+          source,
+          hash,
+        ))
+      });
+
+    let (config, source, content_hash) = parse_res.map_err(|err| {
       anyhow::anyhow!(
         "Failed to parse widget config at '{}': {:?}",
         abs_path.display(),
@@ -389,7 +780,386 @@ impl Config {
       )
     })?;
 
-    Ok((abs_path, config))
+    let source = WidgetConfigSource {
+      value: source,
+      content_hash,
+      base_hashes,
+    };
+
+    Ok((abs_path, config, source))
+  }
+
+  /// Returns the variables available for interpolation in the widget
+  /// config at the given path.
+  ///
+  /// Built-in variables (`configDir` and `widgetDir`) take precedence
+  /// over user-defined ones.
+  fn interpolation_variables(
+    config_dir: &PathBuf,
+    config_path: &PathBuf,
+    user_variables: &HashMap<String, String>,
+  ) -> HashMap<String, String> {
+    let mut variables = user_variables.clone();
+
+    variables.insert("configDir".into(), config_dir.to_unicode_string());
+
+    if let Some(widget_dir) = config_path.parent() {
+      variables.insert("widgetDir".into(), widget_dir.to_unicode_string());
+    }
+
+    variables
+  }
+
+  /// Resolves `extends` references of a widget config and its presets.
+  ///
+  /// Fields are deep-merged, with the extending config or preset taking
+  /// precedence. Presets from a base config are merged by name.
+  ///
+  /// Returns the merged JSON value of the widget config.
+  fn resolve_extends(config_path: &PathBuf, source: &Value) -> anyhow::Result<Value> {
+    let resolved = Self::resolve_config_extends(
+      config_path,
+      source,
+      &mut vec![config_path.clone()],
+      &mut HashMap::new(),
+    )?;
+
+    Self::resolve_preset_extends(resolved)
+  }
+
+  /// Recursively merges the base configs of a widget config.
+  ///
+  /// `chain` holds the config paths visited so far and is used to detect
+  /// cycles. Base config paths are appended to it as they are resolved,
+  /// and the hashes of their contents are added to `base_hashes`.
+  fn resolve_config_extends(
+    config_path: &PathBuf,
+    source: &Value,
+    chain: &mut Vec<PathBuf>,
+    base_hashes: &mut HashMap<PathBuf, u64>,
+  ) -> anyhow::Result<Value> {
+    let base_path = match Self::base_config_path(config_path, source)? {
+      Some(base_path) => base_path,
+      None => return Ok(source.clone()),
+    };
+
+    if chain.contains(&base_path) {
+      chain.push(base_path);
+
+      bail!(
+        "Circular `extends` between widget configs: {}.",
+        chain
+          .iter()
+          .map(|path| format!("'{}'", path.display()))
+          .collect::<Vec<_>>()
+          .join(" -> ")
+      );
+    }
+
+    chain.push(base_path.clone());
+
+    let (base_source, base_hash) = read_migrated(&base_path, ConfigKind::WidgetConfig)?;
+    base_hashes.insert(base_path.clone(), base_hash);
+
+    let mut resolved = Self::resolve_config_extends(&base_path, &base_source, chain, base_hashes)?;
+    Self::rebase_inherited_paths(&mut resolved, &base_path)?;
+    Self::merge_widget_config_json(&mut resolved, source.clone());
+
+    Ok(resolved)
+  }
+
+  /// Rebases paths in a resolved base config onto the base config's
+  /// directory, such that they resolve the same way when inherited by a
+  /// config in another directory.
+  ///
+  /// This covers `${widgetDir}` references and a relative `htmlPath`.
+  fn rebase_inherited_paths(base: &mut Value, base_path: &Path) -> anyhow::Result<()> {
+    let base_dir = base_path.parent().context("No parent directory.")?;
+    let base_dir_str = base_dir.to_unicode_string();
+
+    for (fields, escape) in INTERPOLATED_FIELD_SETS {
+      map_json_strings(base, fields, &mut |string| {
+        *string = replace_variable(string, "widgetDir", &base_dir_str, escape);
+        Ok(())
+      })?;
+    }
+
+    // Paths starting with a variable (e.g. `${configDir}`) are left as-is,
+    // since they are absolute once interpolated.
+    match base.get_mut("htmlPath") {
+      Some(Value::String(html_path))
+        if !html_path.starts_with("${") && Path::new(html_path.as_str()).is_relative() =>
+      {
+        *html_path = base_dir.join(&html_path).to_unicode_string();
+      }
+      _ => {}
+    }
+
+    Ok(())
+  }
+
+  /// Returns the absolute path of the base config referenced by
+  /// `extends`, if any.
+  fn base_config_path(config_path: &Path, source: &Value) -> anyhow::Result<Option<PathBuf>> {
+    let extends = match source.get("extends") {
+      None | Some(Value::Null) => return Ok(None),
+      Some(Value::String(extends)) => extends,
+      Some(_) => bail!(
+        "Invalid `extends` in widget config '{}'. Must be a relative file path.",
+        config_path.display()
+      ),
+    };
+
+    let base_path = config_path
+      .parent()
+      .context("No parent directory.")?
+      .join(extends)
+      .to_absolute()
+      .with_context(|| {
+        format!(
+          "Base config '{}' extended by '{}' not found.",
+          extends,
+          config_path.display()
+        )
+      })?;
+
+    Ok(Some(base_path))
+  }
+
+  /// Deep-merges a widget config JSON value into a base config.
+  ///
+  /// Presets are merged by their name instead of replacing the whole
+  /// array.
+  fn merge_widget_config_json(base: &mut Value, overlay: Value) {
+    let (Some(base_map), Value::Object(mut overlay_map)) = (base.as_object_mut(), overlay) else {
+      return;
+    };
+
+    if let (Some(Value::Array(base_presets)), Some(Value::Array(overlay_presets))) =
+      (base_map.get_mut("presets"), overlay_map.get_mut("presets"))
+    {
+      for overlay_preset in overlay_presets.drain(..) {
+        let name = Self::preset_json_name(&overlay_preset);
+
+        match base_presets
+          .iter_mut()
+          .find(|preset| Self::preset_json_name(preset) == name)
+        {
+          Some(base_preset) => deep_merge(base_preset, overlay_preset),
+          None => base_presets.push(overlay_preset),
+        }
+      }
+
+      overlay_map.remove("presets");
+    }
+
+    deep_merge(base, Value::Object(overlay_map));
+  }
+
+  /// Merges presets with the presets they extend.
+  fn resolve_preset_extends(mut config: Value) -> anyhow::Result<Value> {
+    let presets = match config.get("presets") {
+      Some(Value::Array(presets)) => presets.clone(),
+      _ => return Ok(config),
+    };
+
+    let resolved_presets = presets
+      .iter()
+      .map(|preset| Self::resolve_preset(preset, &presets, &mut vec![]))
+      .collect::<anyhow::Result<Vec<_>>>()?;
+
+    config["presets"] = Value::Array(resolved_presets);
+
+    Ok(config)
+  }
+
+  /// Recursively merges a preset with the preset it extends.
+  ///
+  /// `chain` holds the preset names visited so far and is used to detect
+  /// cycles.
+  fn resolve_preset(
+    preset: &Value,
+    presets: &[Value],
+    chain: &mut Vec<String>,
+  ) -> anyhow::Result<Value> {
+    let name = Self::preset_json_name(preset);
+    chain.push(name.clone());
+
+    let parent_name = match preset.get("extends") {
+      None | Some(Value::Null) => return Ok(preset.clone()),
+      Some(Value::String(parent_name)) => parent_name,
+      Some(_) => bail!(
+        "Invalid `extends` in preset '{}'. Must be a preset name.",
+        name
+      ),
+    };
+
+    if chain.contains(parent_name) {
+      chain.push(parent_name.clone());
+      bail!(
+        "Circular `extends` between presets: {}.",
+        chain.join(" -> ")
+      );
+    }
+
+    let parent = presets
+      .iter()
+      .find(|preset| Self::preset_json_name(preset) == *parent_name)
+      .with_context(|| {
+        format!(
+          "Preset '{}' extends unknown preset '{}'.",
+          name, parent_name
+        )
+      })?;
+
+    let mut resolved = Self::resolve_preset(parent, presets, chain)?;
+    deep_merge(&mut resolved, preset.clone());
+
+    // The name and ID should not be inherited from the parent preset.
+    if let Some(map) = resolved.as_object_mut() {
+      for key in ["name", "id"] {
+        if preset.get(key).is_none() {
+          map.remove(key);
+        }
+      }
+    }
+
+    Ok(resolved)
+  }
+
+  /// Returns the name of a preset JSON value, falling back to the default
+  /// preset name.
+  fn preset_json_name(preset: &Value) -> String {
+    preset
+      .get("name")
+      .and_then(Value::as_str)
+      .map(String::from)
+      .unwrap_or_else(default_preset_name)
+  }
+
+  /// Removes fields from a resolved widget config that are inherited
+  /// through `extends`, so that only the config's own fields are written
+  /// back to disk.
+  ///
+  /// Falls back to the full config if the stripped config does not
+  /// resolve back to the same value.
+  fn strip_extends(config_path: &PathBuf, full: Value, own: &Value) -> anyhow::Result<Value> {
+    let mut stripped = full.clone();
+
+    // Strip fields that are inherited from the base config.
+    if let Some(base_path) = Self::base_config_path(config_path, &full)? {
+      let (base_source, _) = read_migrated(&base_path, ConfigKind::WidgetConfig)?;
+      let mut base = Self::resolve_config_extends(
+        &base_path,
+        &base_source,
+        &mut vec![config_path.clone(), base_path.clone()],
+        &mut HashMap::new(),
+      )?;
+
+      Self::rebase_inherited_paths(&mut base, &base_path)?;
+
+      stripped = Self::strip_widget_config_json(stripped, &base, own);
+    }
+
+    // Strip fields that are inherited from parent presets.
+    let resolved_full = Self::resolve_preset_extends(full.clone())?;
+
+    if let Some(Value::Array(presets)) = stripped.get_mut("presets") {
+      for preset in presets.iter_mut() {
+        let name = Self::preset_json_name(preset);
+
+        let parent = preset
+          .get("extends")
+          .and_then(Value::as_str)
+          .and_then(|parent_name| Self::find_preset_json(&resolved_full, parent_name));
+
+        if let Some(parent) = parent {
+          let own_preset = Self::find_preset_json(own, &name).unwrap_or(Value::Null);
+          *preset = Self::strip_preset_json(preset.clone(), &parent, &own_preset);
+        }
+      }
+    }
+
+    match Self::resolve_extends(config_path, &stripped)?
+      == Self::resolve_extends(config_path, &full)?
+    {
+      true => Ok(stripped),
+      false => {
+        warn!(
+          "Unable to preserve `extends` for widget config '{}'. Writing all fields.",
+          config_path.display()
+        );
+
+        Ok(full)
+      }
+    }
+  }
+
+  /// Removes fields from a widget config JSON value that are equal to the
+  /// ones in the base config. Presets are compared by name.
+  fn strip_widget_config_json(mut config: Value, base: &Value, own: &Value) -> Value {
+    let presets = config.as_object_mut().and_then(|map| map.remove("presets"));
+
+    let mut stripped = strip_inherited(config, base, own);
+
+    if let Some(Value::Array(presets)) = presets {
+      let stripped_presets = presets
+        .into_iter()
+        .filter_map(|preset| {
+          let name = Self::preset_json_name(&preset);
+          let own_preset = Self::find_preset_json(own, &name);
+
+          match Self::find_preset_json(base, &name) {
+            None => Some(preset),
+            Some(base_preset) => {
+              let preset = Self::strip_preset_json(
+                preset,
+                &base_preset,
+                own_preset.as_ref().unwrap_or(&Value::Null),
+              );
+
+              // Omit presets that are identical to the base preset.
+              let is_unchanged = preset.as_object().is_some_and(|map| map.len() <= 1);
+
+              match is_unchanged && own_preset.is_none() {
+                true => None,
+                false => Some(preset),
+              }
+            }
+          }
+        })
+        .collect::<Vec<_>>();
+
+      if let Some(map) = stripped.as_object_mut() {
+        map.insert("presets".into(), Value::Array(stripped_presets));
+      }
+    }
+
+    stripped
+  }
+
+  /// Removes fields from a preset that are equal to the ones in the
+  /// inherited preset. The preset name is always kept.
+  fn strip_preset_json(preset: Value, inherited: &Value, own: &Value) -> Value {
+    let name = preset.get("name").cloned();
+    let mut stripped = strip_inherited(preset, inherited, own);
+
+    if let (Some(map), Some(name)) = (stripped.as_object_mut(), name) {
+      map.insert("name".into(), name);
+    }
+
+    stripped
+  }
+
+  /// Returns the preset with the given name from a widget config JSON
+  /// value.
+  fn find_preset_json(config: &Value, name: &str) -> Option<Value> {
+    config
+      .get("presets")?
+      .as_array()?
+      .iter()
+      .find(|preset| Self::preset_json_name(preset) == name)
+      .cloned()
   }
 
   /// Initializes settings and widget configs at the given path.
@@ -415,10 +1185,15 @@ impl Config {
       schema: Some(
         "https://github.com/krishenriksen/edgebar/raw/v2.4.0/resources/menu-schema.json".into(),
       ),
+      version: CONFIG_VERSION,
       startup_configs: vec![StartupConfig {
         path: "macos/macos.edgebar.json".into(),
         preset: "default".into(),
       }],
+      variables: HashMap::new(),
+      settings: RuntimeSettings::default(),
+      profiles: Vec::new(),
+      active_profile: None,
     };
 
     let settings_path = config_dir.join("settings.json");
@@ -434,13 +1209,200 @@ impl Config {
     self.widget_configs.lock().await.clone()
   }
 
+  /// Returns the path to the file that open widgets are persisted to.
+  pub fn session_path(&self) -> PathBuf {
+    self.config_dir.join("session.json")
+  }
+
+  /// Returns the path to the file that user-adjusted widget placements
+  /// are persisted to.
+  pub fn user_placements_path(&self) -> PathBuf {
+    self.config_dir.join("placements.json")
+  }
+
   /// Returns the widget configs to open on startup.
+  ///
+  /// Uses the startup configs of the active profile if one is set.
   pub async fn startup_configs(&self) -> Vec<StartupConfig> {
-    self.settings.lock().await.startup_configs.clone()
+    let settings = self.settings.lock().await;
+
+    match settings.active_profile() {
+      Some(profile) => profile.startup_configs.clone(),
+      None => settings.startup_configs.clone(),
+    }
+  }
+
+  /// Returns the names of all profiles and the name of the active one.
+  pub async fn profiles(&self) -> (Vec<String>, Option<String>) {
+    let settings = self.settings.lock().await;
+
+    let names = settings
+      .profiles
+      .iter()
+      .map(|profile| profile.name.clone())
+      .collect();
+
+    (names, settings.active_profile.clone())
+  }
+
+  /// Returns the user-defined variables with overrides from the active
+  /// profile applied.
+  async fn variables(&self) -> HashMap<String, String> {
+    let settings = self.settings.lock().await;
+    settings.variables_for(settings.active_profile.as_deref())
+  }
+
+  /// Activates the profile with the given name and re-reads widget
+  /// configs with the profile's variable overrides.
+  ///
+  /// The active profile is persisted to the settings file. Changed
+  /// widget configs are not broadcast, since the caller is responsible
+  /// for closing and relaunching widgets as part of the switch (see
+  /// `WidgetFactory::switch_profile`).
+  ///
+  /// Returns the widget configs that changed.
+  pub async fn set_active_profile(
+    &self,
+    name: &str,
+  ) -> anyhow::Result<HashMap<PathBuf, WidgetConfig>> {
+    let settings = {
+      let mut settings = self.settings.lock().await;
+
+      settings
+        .profile(name)
+        .with_context(|| format!("No profile named '{}' in settings.", name))?;
+
+      info!("Switching to profile '{}'.", name);
+      Self::write_active_profile(&self.settings_path, name)?;
+      settings.active_profile = Some(name.into());
+      settings.clone()
+    };
+
+    let variables = settings.variables_for(Some(name));
+    let changed_configs = self.reload_widget_configs(&variables).await?;
+
+    // Notify listeners (e.g. the system tray) of the active profile.
+    let _ = self.settings_change_tx.send(settings);
+
+    Ok(changed_configs)
+  }
+
+  /// Sets `activeProfile` in the settings file, keeping the rest of the
+  /// file as-is.
+  fn write_active_profile(settings_path: &PathBuf, name: &str) -> anyhow::Result<()> {
+    let mut value = read_and_parse_config::<Value>(settings_path)?;
+
+    value
+      .as_object_mut()
+      .context("Settings must be a JSON object.")?
+      .insert("activeProfile".into(), name.into());
+
+    write_config(settings_path, &value)?;
+
+    Ok(())
+  }
+
+  /// Re-reads all widget configs with the given variables.
+  ///
+  /// Returns the widget configs that changed.
+  async fn reload_widget_configs(
+    &self,
+    variables: &HashMap<String, String>,
+  ) -> anyhow::Result<HashMap<PathBuf, WidgetConfig>> {
+    let (new_configs, new_sources) = Self::read_widget_configs(&self.config_dir, variables)?;
+
+    let changed_configs = {
+      let widget_configs = self.widget_configs.lock().await;
+
+      new_configs
+        .iter()
+        .filter(|(path, config)| {
+          widget_configs
+            .get(*path)
+            .map(serde_json::to_value)
+            .and_then(Result::ok)
+            != serde_json::to_value(config).ok()
+        })
+        .map(|(path, config)| (path.clone(), config.clone()))
+        .collect()
+    };
+
+    *self.widget_configs.lock().await = new_configs;
+    *self.widget_config_sources.lock().await = new_sources;
+
+    Ok(changed_configs)
+  }
+
+  /// Returns the runtime options from the global settings.
+  pub async fn runtime_settings(&self) -> RuntimeSettings {
+    self.settings.lock().await.settings.clone()
+  }
+
+  /// Re-reads the global settings file and broadcasts the change.
+  ///
+  /// Widget configs are re-read if the variables available to them
+  /// changed, and any changed configs are broadcast as well.
+  pub async fn reload_settings(&self) -> anyhow::Result<()> {
+    let (_, new_settings) = Self::read_settings(&self.config_dir)?.with_context(|| {
+      format!(
+        "Settings file at '{}' no longer exists.",
+        self.settings_path.display()
+      )
+    })?;
+
+    let prev_variables = self.variables().await;
+
+    *self.settings.lock().await = new_settings.clone();
+    self.settings_change_tx.send(new_settings)?;
+
+    let variables = self.variables().await;
+
+    if variables != prev_variables {
+      info!("Variables changed. Re-reading widget configs.");
+      let changed_configs = self.reload_widget_configs(&variables).await?;
+
+      if !changed_configs.is_empty() {
+        self.widget_configs_change_tx.send(changed_configs)?;
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Polls the settings file for changes made on disk, and reloads the
+  /// settings whenever its content changes.
+  pub fn watch_settings(self: Arc<Self>) {
+    task::spawn(async move {
+      let mut interval = tokio::time::interval(SETTINGS_POLL_INTERVAL);
+      interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+      let mut last_hash = read_content_hash(&self.settings_path).ok();
+
+      loop {
+        interval.tick().await;
+
+        // Skip while the file is missing (e.g. mid-save by an editor).
+        let hash = read_content_hash(&self.settings_path).ok();
+
+        if hash.is_none() || hash == last_hash {
+          continue;
+        }
+
+        last_hash = hash;
+        info!("Settings file changed on disk. Reloading settings.");
+
+        if let Err(err) = self.reload_settings().await {
+          warn!("Failed to reload settings: {:?}", err);
+        }
+      }
+    });
   }
 
   /// Updates the widget config at the given path.
   ///
+  /// The file is written atomically and the change is only broadcast
+  /// once written. Errors without writing if the file, or a base config
+  /// it extends, has been modified on disk since it was last read.
+  ///
   /// Config path can be either absolute or relative.
   pub async fn update_widget_config(
     &self,
@@ -449,28 +1411,94 @@ impl Config {
   ) -> anyhow::Result<()> {
     info!("Updating widget config at {}.", config_path.display());
 
+    if !self.widget_configs.lock().await.contains_key(config_path) {
+      bail!("Widget config not found at {}.", config_path.display());
+    }
+
+    let user_variables = self.variables().await;
+    let variables = Self::interpolation_variables(&self.config_dir, config_path, &user_variables);
+
     {
-      let mut widget_configs = self.widget_configs.lock().await;
+      // Hold the lock until the file is written, so that concurrent
+      // updates are serialized.
+      let mut sources = self.widget_config_sources.lock().await;
 
-      let config_entry = widget_configs.get_mut(config_path).context(format!(
-        "Widget config not found at {}.",
-        config_path.display()
-      ))?;
+      let source = sources.get(config_path);
+
+      // Avoid overwriting changes that were made on disk in the meantime.
+      if let Some(source) = source {
+        Self::check_unmodified(config_path, source)?;
+      }
+
+      // Restore variable references that are unchanged by the update, so
+      // that they are preserved in the written file.
+      let new_value = serde_json::to_value(&new_config)?;
+
+      let new_source = match source {
+        Some(source) => {
+          let resolved_source = Self::resolve_extends(config_path, &source.value)?;
 
-      // Update the config in state.
-      *config_entry = new_config.clone();
+          let new_value =
+            INTERPOLATED_FIELD_SETS
+              .into_iter()
+              .fold(new_value, |value, (fields, escape)| {
+                restore_templates(value, &resolved_source, fields, &variables, escape)
+              });
+
+          Self::strip_extends(config_path, new_value, &source.value)?
+        }
+        None => new_value,
+      };
+
+      // Write the updated config to file in its original format.
+      let content = write_config(config_path, &new_source)?;
+
+      let base_hashes = source
+        .map(|source| source.base_hashes.clone())
+        .unwrap_or_default();
+
+      sources.insert(
+        config_path.clone(),
+        WidgetConfigSource {
+          value: new_source,
+          content_hash: content_hash(&content),
+          base_hashes,
+        },
+      );
     }
 
+    // Update the config in state.
+    self
+      .widget_configs
+      .lock()
+      .await
+      .insert(config_path.clone(), new_config.clone());
+
     // Emit the changed config.
     self
       .widget_configs_change_tx
-      .send(HashMap::from([(config_path.clone(), new_config.clone())]))?;
+      .send(HashMap::from([(config_path.clone(), new_config)]))?;
 
-    // Write the updated config to file.
-    fs::write(
-      &config_path,
-      serde_json::to_string_pretty(&new_config)? + "\n",
-    )?;
+    Ok(())
+  }
+
+  /// Errors if the widget config, or any of the base configs it
+  /// inherits through `extends`, was modified on disk since it was last
+  /// read.
+  fn check_unmodified(config_path: &PathBuf, source: &WidgetConfigSource) -> anyhow::Result<()> {
+    let is_modified = read_content_hash(config_path)? != source.content_hash
+      || source
+        .base_hashes
+        .iter()
+        .any(|(base_path, hash)| read_content_hash(base_path).ok() != Some(*hash));
+
+    if is_modified {
+      bail!(
+        "Widget config at {} was modified on disk since it was last read. \
+        Reload the config before saving.",
+        config_path.display()
+      );
+    }
 
     Ok(())
   }
@@ -498,7 +1526,7 @@ impl Config {
 
   /// Formats a widget's config path for display.
   ///
-  /// Returns relative path without the `.edgebar.json` suffix (e.g.
+  /// Returns relative path without the `.edgebar.*` suffix (e.g.
   /// `starter/vanilla`).
   pub fn formatted_widget_path(&self, config_path: &PathBuf) -> String {
     let path = self.to_relative_path(config_path).to_unicode_string();
@@ -506,7 +1534,11 @@ impl Config {
     // Ensure path delimiters are forward slashes on Windows.
     let path = path.replace('\\', "/");
 
-    path.strip_suffix(".edgebar.json").unwrap_or(&path).into()
+    ConfigFormat::ALL
+      .iter()
+      .find_map(|format| path.strip_suffix(&format!(".edgebar.{}", format.extension())))
+      .unwrap_or(&path)
+      .into()
   }
 
   /// Returns the widget config at the given path.
@@ -530,8 +1562,336 @@ const fn default_bool<const V: bool>() -> bool {
   V
 }
 
+/// Helper function for setting the default value for a `version` field.
+///
+/// Configs that are created in-memory (e.g. by the client) are assumed to
+/// be of the current schema version.
+const fn default_config_version() -> u32 {
+  CONFIG_VERSION
+}
+
 /// Helper function for setting the default value for a
 /// `WidgetPreset::name` field.
 fn default_preset_name() -> String {
   "default".into()
 }
+
+/// Helper function for setting the default value for a
+/// `RuntimeSettings::ignored_window_titles` field.
+#[cfg(target_os = "windows")]
+fn default_ignored_titles() -> Vec<String> {
+  window_util::DEFAULT_IGNORED_TITLES
+    .iter()
+    .map(|title| title.to_string())
+    .collect()
+}
+
+/// Helper function for setting the default value for a
+/// `RuntimeSettings::ignored_window_titles` field.
+///
+/// Window titles are only matched on Windows, so none are ignored by
+/// default elsewhere.
+#[cfg(not(target_os = "windows"))]
+fn default_ignored_titles() -> Vec<String> {
+  vec![]
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+
+  use super::*;
+  use crate::common::temp_dir;
+
+  /// Writes a JSON config file, creating its parent directories.
+  fn write_json(path: &PathBuf, value: Value) {
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, value.to_string()).unwrap();
+  }
+
+  #[test]
+  fn test_resolve_extends_rebases_inherited_paths() {
+    let dir = temp_dir();
+    let base_path = dir.join("base/base.edgebar.json");
+    let config_path = dir.join("child/child.edgebar.json");
+
+    write_json(
+      &base_path,
+      json!({
+        "version": 1,
+        "htmlPath": "index.html",
+        "privileges": {
+          "shellCommands": [{ "program": "${widgetDir}/run.sh", "argsRegex": ".*" }],
+        },
+      }),
+    );
+
+    let source = json!({
+      "version": 1,
+      "extends": "../base/base.edgebar.json",
+      "presets": [{ "name": "default", "width": "${widgetDir}" }],
+    });
+
+    write_json(&config_path, source.clone());
+
+    let resolved = Config::resolve_extends(&config_path, &source).unwrap();
+    let base_dir = base_path.parent().unwrap();
+
+    assert_eq!(
+      resolved["htmlPath"],
+      json!(base_dir.join("index.html").to_unicode_string())
+    );
+    assert_eq!(
+      resolved["privileges"]["shellCommands"][0]["program"],
+      json!(format!("{}/run.sh", base_dir.to_unicode_string()))
+    );
+    // The extending config's own fields are not rebased.
+    assert_eq!(resolved["presets"][0]["width"], json!("${widgetDir}"));
+
+    fs::remove_dir_all(dir).unwrap();
+  }
+
+  #[test]
+  fn test_resolve_extends_migrates_base_in_memory() {
+    let dir = temp_dir();
+    let base_path = dir.join("base.edgebar.json");
+    let config_path = dir.join("child.edgebar.json");
+    let base_source = json!({ "defaultPlacements": [{ "name": "default", "width": "10" }] });
+
+    write_json(&base_path, base_source.clone());
+
+    let source = json!({ "version": 1, "extends": "base.edgebar.json" });
+    write_json(&config_path, source.clone());
+
+    let resolved = Config::resolve_extends(&config_path, &source).unwrap();
+    assert_eq!(resolved["presets"][0]["width"], json!("10"));
+
+    let stripped = Config::strip_extends(&config_path, resolved, &source).unwrap();
+    assert_eq!(stripped["presets"], json!([]));
+
+    // The shared base is neither rewritten nor backed up.
+    let on_disk = serde_json::from_str::<Value>(&fs::read_to_string(&base_path).unwrap());
+    assert_eq!(on_disk.unwrap(), base_source);
+    assert!(!dir.join("base.edgebar.json.v0.bak").exists());
+
+    fs::remove_dir_all(dir).unwrap();
+  }
+
+  #[test]
+  fn test_resolve_extends_detects_cycles() {
+    let dir = temp_dir();
+    let config_path = dir.join("a/a.edgebar.json");
+    let source = json!({ "version": 1, "extends": "../b/b.edgebar.json" });
+
+    write_json(&config_path, source.clone());
+    write_json(
+      &dir.join("b/b.edgebar.json"),
+      json!({ "version": 1, "extends": "../a/a.edgebar.json" }),
+    );
+
+    let err = Config::resolve_extends(&config_path, &source).unwrap_err();
+    assert!(err
+      .to_string()
+      .starts_with("Circular `extends` between widget configs"));
+
+    fs::remove_dir_all(dir).unwrap();
+  }
+
+  #[test]
+  fn test_resolve_extends_missing_base() {
+    let dir = temp_dir();
+    let config_path = dir.join("a/a.edgebar.json");
+    let source = json!({ "version": 1, "extends": "../missing.edgebar.json" });
+
+    let err = Config::resolve_extends(&config_path, &source).unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      format!(
+        "Base config '../missing.edgebar.json' extended by '{}' not found.",
+        config_path.display()
+      )
+    );
+
+    fs::remove_dir_all(dir).unwrap();
+  }
+
+  #[test]
+  fn test_resolve_preset_extends() {
+    let config = json!({
+      "presets": [
+        { "name": "default", "id": "bar", "width": "100%", "height": "40px" },
+        { "name": "secondary", "extends": "default", "height": "30px" },
+      ],
+    });
+
+    let resolved = Config::resolve_preset_extends(config).unwrap();
+
+    assert_eq!(
+      resolved["presets"][1],
+      json!({
+        "name": "secondary",
+        "extends": "default",
+        "width": "100%",
+        "height": "30px",
+      })
+    );
+  }
+
+  #[test]
+  fn test_resolve_preset_extends_detects_cycles() {
+    let config = json!({
+      "presets": [
+        { "name": "a", "extends": "b" },
+        { "name": "b", "extends": "a" },
+      ],
+    });
+
+    let err = Config::resolve_preset_extends(config).unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "Circular `extends` between presets: a -> b -> a."
+    );
+  }
+
+  #[test]
+  fn test_strip_extends_keeps_own_fields() {
+    let dir = temp_dir();
+    let config_path = dir.join("child/child.edgebar.json");
+
+    write_json(
+      &dir.join("base/base.edgebar.json"),
+      json!({
+        "version": 1,
+        "htmlPath": "index.html",
+        "focused": false,
+        "presets": [{ "name": "default", "width": "100%" }],
+      }),
+    );
+
+    let own = json!({
+      "version": 1,
+      "extends": "../base/base.edgebar.json",
+      "focused": false,
+      "presets": [{ "name": "default", "height": "40px" }],
+    });
+
+    write_json(&config_path, own.clone());
+
+    let full = Config::resolve_extends(&config_path, &own).unwrap();
+    let stripped = Config::strip_extends(&config_path, full, &own).unwrap();
+
+    assert_eq!(stripped, own);
+
+    fs::remove_dir_all(dir).unwrap();
+  }
+
+  #[test]
+  fn test_check_unmodified_detects_conflicts() {
+    let dir = temp_dir();
+    let base_path = dir.join("base.edgebar.json");
+    let config_path = dir.join("child.edgebar.json");
+
+    write_json(
+      &base_path,
+      json!({ "version": 1, "htmlPath": "index.html" }),
+    );
+    write_json(
+      &config_path,
+      json!({ "version": 1, "extends": "base.edgebar.json" }),
+    );
+
+    let source = WidgetConfigSource {
+      value: Value::Null,
+      content_hash: read_content_hash(&config_path).unwrap(),
+      base_hashes: HashMap::from([(base_path.clone(), read_content_hash(&base_path).unwrap())]),
+    };
+
+    assert!(Config::check_unmodified(&config_path, &source).is_ok());
+
+    // Edited on disk in the meantime.
+    write_json(
+      &base_path,
+      json!({ "version": 1, "htmlPath": "other.html" }),
+    );
+
+    let err = Config::check_unmodified(&config_path, &source).unwrap_err();
+    assert!(err.to_string().contains("was modified on disk"));
+
+    let source = WidgetConfigSource {
+      base_hashes: HashMap::new(),
+      ..source
+    };
+
+    fs::write(&config_path, "{ \"version\": 1 }").unwrap();
+    assert!(Config::check_unmodified(&config_path, &source).is_err());
+
+    fs::remove_dir_all(dir).unwrap();
+  }
+
+  #[test]
+  fn test_write_active_profile_preserves_settings() {
+    let dir = temp_dir();
+    let settings_path = dir.join("settings.jsonc");
+
+    fs::write(
+      &settings_path,
+      "{\n  // Opened on startup.\n  \"startupConfigs\": [],\n  \"profiles\": [{ \"name\": \"home\", \"startupConfigs\": [] }]\n}",
+    )
+    .unwrap();
+
+    Config::write_active_profile(&settings_path, "home").unwrap();
+
+    let content = fs::read_to_string(&settings_path).unwrap();
+    assert!(content.contains("// Opened on startup."));
+
+    let (_, settings) = Config::read_settings(&dir).unwrap().unwrap();
+    assert_eq!(settings.active_profile.as_deref(), Some("home"));
+    assert_eq!(settings.profiles.len(), 1);
+
+    fs::remove_dir_all(dir).unwrap();
+  }
+
+  #[test]
+  fn test_read_settings_ignores_unknown_active_profile() {
+    let dir = temp_dir();
+
+    write_json(
+      &dir.join("settings.json"),
+      json!({ "startupConfigs": [], "activeProfile": "removed" }),
+    );
+
+    let (_, settings) = Config::read_settings(&dir).unwrap().unwrap();
+    assert_eq!(settings.active_profile, None);
+
+    fs::remove_dir_all(dir).unwrap();
+  }
+
+  #[test]
+  fn test_active_profile_variables() {
+    let settings = serde_json::from_value::<SettingsConfig>(json!({
+      "startupConfigs": [{ "path": "a/a.edgebar.json", "preset": "default" }],
+      "variables": { "theme": "dark", "scale": "1" },
+      "profiles": [{
+        "name": "presentation",
+        "startupConfigs": [],
+        "variables": { "scale": "2" },
+      }],
+      "activeProfile": "presentation",
+    }))
+    .unwrap();
+
+    assert!(settings
+      .active_profile()
+      .unwrap()
+      .startup_configs
+      .is_empty());
+    assert_eq!(
+      settings.variables_for(settings.active_profile.as_deref()),
+      HashMap::from([
+        ("theme".to_string(), "dark".to_string()),
+        ("scale".to_string(), "2".to_string()),
+      ])
+    );
+    assert_eq!(settings.variables_for(None)["scale"], "1");
+  }
+}
@@ -0,0 +1,303 @@
+use std::{
+  fs,
+  path::{Path, PathBuf},
+};
+
+use anyhow::{Context, bail};
+use serde_json::{Value, json};
+use tracing::info;
+
+use crate::common::{content_hash, read_and_parse_config_hashed, write_config};
+
+/// Current schema version of settings and widget configs.
+pub const CONFIG_VERSION: u32 = 1;
+
+/// A one-way upgrade of a config's raw JSON to the next schema version.
+struct Migration {
+  /// Version that the config is at after the migration.
+  to_version: u32,
+
+  /// Short summary of the schema change.
+  description: &'static str,
+
+  /// Transforms the raw JSON of the previous version.
+  migrate: fn(Value) -> anyhow::Result<Value>,
+}
+
+/// Migrations for `settings.json`, ordered by version.
+const SETTINGS_MIGRATIONS: &[Migration] = &[Migration {
+  to_version: 1,
+  description: "Convert string entries in `startupConfigs` to objects.",
+  migrate: settings_v1,
+}];
+
+/// Migrations for `*.edgebar.*` widget configs, ordered by version.
+const WIDGET_CONFIG_MIGRATIONS: &[Migration] = &[Migration {
+  to_version: 1,
+  description: "Rename `defaultPlacements` to `presets`.",
+  migrate: widget_config_v1,
+}];
+
+/// Kind of config file to migrate.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ConfigKind {
+  Settings,
+  WidgetConfig,
+}
+
+impl ConfigKind {
+  fn migrations(&self) -> &'static [Migration] {
+    match self {
+      Self::Settings => SETTINGS_MIGRATIONS,
+      Self::WidgetConfig => WIDGET_CONFIG_MIGRATIONS,
+    }
+  }
+}
+
+/// Reads a top-level config file and upgrades it to the current schema
+/// version.
+///
+/// If any migrations were applied, the original file is backed up as
+/// `<file>.v<version>.bak` and the upgraded config is written in its
+/// place. This should only be used when loading settings and widget
+/// configs; base configs are shared and are migrated in memory instead
+/// (see `read_migrated`).
+///
+/// Returns the upgraded JSON value and a hash of the file content it
+/// corresponds to.
+pub fn read_and_migrate(path: &PathBuf, kind: ConfigKind) -> anyhow::Result<(Value, u64)> {
+  let (migrated, hash, from_version) = read_migrated_with_version(path, kind)?;
+
+  if from_version < CONFIG_VERSION {
+    let backup_path = backup_path(path, from_version);
+
+    info!(
+      "Upgrading config at '{}' from v{} to v{}. Backup written to '{}'.",
+      path.display(),
+      from_version,
+      CONFIG_VERSION,
+      backup_path.display()
+    );
+
+    // Keep the oldest backup if one already exists for this version.
+    if !backup_path.exists() {
+      fs::copy(path, &backup_path)
+        .with_context(|| format!("Failed to back up config at '{}'.", path.display()))?;
+    }
+
+    let content = write_config(path, &migrated)?;
+
+    return Ok((migrated, content_hash(&content)));
+  }
+
+  Ok((migrated, hash))
+}
+
+/// Reads a config file and upgrades it to the current schema version
+/// without writing it back to disk.
+///
+/// Returns the upgraded JSON value and a hash of the file content.
+pub fn read_migrated(path: &PathBuf, kind: ConfigKind) -> anyhow::Result<(Value, u64)> {
+  read_migrated_with_version(path, kind).map(|(migrated, hash, _)| (migrated, hash))
+}
+
+/// Reads and migrates a config file. Returns the upgraded JSON value, a
+/// hash of the file content and the version the file was at.
+fn read_migrated_with_version(
+  path: &PathBuf,
+  kind: ConfigKind,
+) -> anyhow::Result<(Value, u64, u32)> {
+  let (value, hash) = read_and_parse_config_hashed::<Value>(path)?;
+  let from_version = config_version(&value)?;
+
+  let migrated = migrate(value, kind)
+    .with_context(|| format!("Failed to migrate config at '{}'.", path.display()))?;
+
+  Ok((migrated, hash, from_version))
+}
+
+/// Applies all pending migrations to the raw JSON of a config.
+///
+/// Configs without a `version` field are treated as version 0.
+pub fn migrate(mut value: Value, kind: ConfigKind) -> anyhow::Result<Value> {
+  if !value.is_object() {
+    bail!("Config must be a JSON object.");
+  }
+
+  let version = config_version(&value)?;
+
+  if version > CONFIG_VERSION {
+    bail!(
+      "Config version {} is newer than the latest supported version {}.",
+      version,
+      CONFIG_VERSION
+    );
+  }
+
+  for migration in kind
+    .migrations()
+    .iter()
+    .filter(|migration| migration.to_version > version)
+  {
+    info!(
+      "Applying config migration to v{}: {}",
+      migration.to_version, migration.description
+    );
+
+    value = (migration.migrate)(value)?;
+    value["version"] = json!(migration.to_version);
+  }
+
+  Ok(value)
+}
+
+/// Returns the `version` field of a config's raw JSON.
+fn config_version(value: &Value) -> anyhow::Result<u32> {
+  match value.get("version") {
+    None | Some(Value::Null) => Ok(0),
+    Some(version) => version
+      .as_u64()
+      .and_then(|version| u32::try_from(version).ok())
+      .context("Config `version` must be a non-negative integer."),
+  }
+}
+
+/// Returns the path to back up a config to prior to migrating.
+fn backup_path(path: &Path, version: u32) -> PathBuf {
+  let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+  file_name.push(format!(".v{}.bak", version));
+
+  path.with_file_name(file_name)
+}
+
+/// Converts string entries in `startupConfigs` (v2.3.0 and earlier) to
+/// objects with the default preset.
+fn settings_v1(mut value: Value) -> anyhow::Result<Value> {
+  if let Some(Value::Array(startup_configs)) = value.get_mut("startupConfigs") {
+    for startup_config in startup_configs.iter_mut() {
+      if let Value::String(path) = startup_config {
+        *startup_config = json!({ "path": path, "preset": "default" });
+      }
+    }
+  }
+
+  Ok(value)
+}
+
+/// Renames `defaultPlacements` (v2.3.0 and earlier) to `presets`.
+fn widget_config_v1(mut value: Value) -> anyhow::Result<Value> {
+  let Some(map) = value.as_object_mut() else {
+    return Ok(value);
+  };
+
+  if let Some(placements) = map.remove("defaultPlacements") {
+    if map.contains_key("presets") {
+      bail!("Config cannot contain both `defaultPlacements` and `presets`.");
+    }
+
+    map.insert("presets".into(), placements);
+  }
+
+  Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::common::{read_content_hash, temp_dir};
+
+  #[test]
+  fn test_settings_v1_converts_string_startup_configs() {
+    let value = json!({
+      "startupConfigs": [
+        "starter/vanilla.edgebar.json",
+        { "path": "starter/with-glazewm.edgebar.json", "preset": "secondary" }
+      ]
+    });
+
+    let migrated = migrate(value, ConfigKind::Settings).unwrap();
+
+    assert_eq!(
+      migrated,
+      json!({
+        "startupConfigs": [
+          { "path": "starter/vanilla.edgebar.json", "preset": "default" },
+          { "path": "starter/with-glazewm.edgebar.json", "preset": "secondary" }
+        ],
+        "version": 1
+      })
+    );
+  }
+
+  #[test]
+  fn test_widget_config_v1_renames_default_placements() {
+    let value = json!({ "defaultPlacements": [{ "name": "default" }] });
+
+    let migrated = migrate(value, ConfigKind::WidgetConfig).unwrap();
+
+    assert_eq!(
+      migrated,
+      json!({ "presets": [{ "name": "default" }], "version": 1 })
+    );
+  }
+
+  #[test]
+  fn test_current_version_is_unchanged() {
+    let value = json!({ "version": CONFIG_VERSION, "defaultPlacements": [] });
+
+    let migrated = migrate(value.clone(), ConfigKind::WidgetConfig).unwrap();
+
+    assert_eq!(migrated, value);
+  }
+
+  #[test]
+  fn test_newer_version_is_rejected() {
+    let value = json!({ "version": CONFIG_VERSION + 1 });
+
+    assert!(migrate(value, ConfigKind::Settings).is_err());
+  }
+
+  #[test]
+  fn test_backup_path() {
+    let path = PathBuf::from("/edgebar/settings.json");
+
+    assert_eq!(
+      backup_path(&path, 0),
+      PathBuf::from("/edgebar/settings.json.v0.bak")
+    );
+  }
+
+  #[test]
+  fn test_read_and_migrate_backs_up_and_rewrites() {
+    let dir = temp_dir();
+    let path = dir.join("settings.json");
+    let backup_path = dir.join("settings.json.v0.bak");
+    let source = r#"{ "startupConfigs": ["starter/vanilla.edgebar.json"] }"#;
+
+    fs::write(&path, source).unwrap();
+
+    let (migrated, hash) = read_and_migrate(&path, ConfigKind::Settings).unwrap();
+
+    assert_eq!(migrated["version"], json!(CONFIG_VERSION));
+    assert_eq!(fs::read_to_string(&backup_path).unwrap(), source);
+
+    // The file is rewritten at the current version, and the returned
+    // hash matches the written content.
+    let (written, _) = read_and_parse_config_hashed::<Value>(&path).unwrap();
+    assert_eq!(written, migrated);
+    assert_eq!(read_content_hash(&path).unwrap(), hash);
+
+    // An existing backup is kept when migrating again.
+    fs::write(&path, r#"{ "startupConfigs": [] }"#).unwrap();
+    read_and_migrate(&path, ConfigKind::Settings).unwrap();
+    assert_eq!(fs::read_to_string(&backup_path).unwrap(), source);
+
+    // Configs at the current version are left as-is.
+    let content = fs::read_to_string(&path).unwrap();
+    let (_, hash) = read_and_migrate(&path, ConfigKind::Settings).unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), content);
+    assert_eq!(hash, content_hash(&content));
+
+    fs::remove_dir_all(dir).unwrap();
+  }
+}
@@ -0,0 +1,71 @@
+/// A widget that is docked to a monitor edge.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DockSlot {
+  /// Position in the stack. Lower orders are docked closer to the edge.
+  pub order: i32,
+
+  /// Running count of when the widget was docked. Used to order widgets
+  /// with the same `order`.
+  pub sequence: u32,
+
+  /// Length to reserve for the widget perpendicular to the edge.
+  pub length: i32,
+}
+
+/// Stacks widgets that are docked to the same monitor edge.
+///
+/// Returns the offset from the monitor edge for each slot, in the same
+/// order as the given slots.
+pub fn stack_dock_slots(slots: &[DockSlot]) -> Vec<i32> {
+  let mut stacking_order = (0..slots.len()).collect::<Vec<_>>();
+  stacking_order.sort_by_key(|&index| (slots[index].order, slots[index].sequence));
+
+  let mut offsets = vec![0; slots.len()];
+  let mut offset = 0;
+
+  for index in stacking_order {
+    offsets[index] = offset;
+    offset += slots[index].length.max(0);
+  }
+
+  offsets
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn slot(order: i32, sequence: u32, length: i32) -> DockSlot {
+    DockSlot {
+      order,
+      sequence,
+      length,
+    }
+  }
+
+  #[test]
+  fn test_stacks_by_order() {
+    let slots = [slot(1, 1, 30), slot(0, 2, 40), slot(2, 3, 20)];
+
+    assert_eq!(stack_dock_slots(&slots), vec![40, 0, 70]);
+  }
+
+  #[test]
+  fn test_stacks_equal_orders_by_sequence() {
+    let slots = [slot(0, 5, 30), slot(0, 2, 40), slot(-1, 9, 10)];
+
+    assert_eq!(stack_dock_slots(&slots), vec![50, 10, 0]);
+  }
+
+  #[test]
+  fn test_ignores_negative_lengths() {
+    let slots = [slot(0, 1, -10), slot(0, 2, 40)];
+
+    assert_eq!(stack_dock_slots(&slots), vec![0, 0]);
+  }
+
+  #[test]
+  fn test_empty_slots() {
+    assert_eq!(stack_dock_slots(&[]), Vec::<i32>::new());
+  }
+}
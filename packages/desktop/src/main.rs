@@ -7,19 +7,23 @@ use clap::Parser;
 use tauri::{async_runtime::block_on, AppHandle, Emitter, Manager, RunEvent};
 use tokio::{sync::mpsc, task};
 use tracing::{error, info, level_filters::LevelFilter};
-use tracing_subscriber::EnvFilter;
+use tracing_subscriber::{
+  fmt, layer::SubscriberExt, reload, util::SubscriberInitExt, EnvFilter, Registry,
+};
 
 use crate::{
-  asset_server::setup_asset_server,
-  cli::{Cli, CliCommand, MonitorType, QueryArgs},
-  config::{Config, MonitorSelection, WidgetPlacement},
+  asset_server::{setup_asset_server, DEFAULT_ASSET_SERVER_PORT},
+  cli::{Cli, CliCommand, QueryArgs, RevealArgs, WidgetVisibilityArgs},
+  config::{Config, RuntimeSettings, WidgetPlacement},
+  message_bus::MessageBus,
   monitor_state::MonitorState,
   providers::{ProviderEmission, ProviderManager},
   shell_state::ShellState,
   sys_tray::SysTray,
-  widget_factory::{WidgetFactory, WidgetOpenOptions},
+  widget_factory::{VisibilityAction, WidgetFactory, WidgetOpenOptions},
 };
 
+#[cfg(target_os = "windows")]
 use menu_util::initialize_menu_window;
 
 mod asset_server;
@@ -27,15 +31,25 @@ mod cli;
 mod commands;
 mod common;
 mod config;
+mod config_migrations;
+mod dock_layout;
+mod message_bus;
 mod monitor_state;
+// Providers are only implemented on Windows so far.
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
 mod providers;
+mod session;
 mod shell_state;
 mod sys_tray;
+mod user_placements;
 mod widget_factory;
 
 #[macro_use]
 extern crate rocket;
 
+/// Handle for swapping out the log filter at runtime.
+type LogFilterHandle = reload::Handle<EnvFilter, Registry>;
+
 /// Main entry point for the application.
 ///
 /// Conditionally starts EdgeBar or runs a CLI command based on the given
@@ -54,8 +68,8 @@ async fn main() -> anyhow::Result<()> {
   let app = tauri::Builder::default()
     .setup(|app| {
       // Initialize the menu window
-      let app_handle = app.handle();
-      if let Err(err) = initialize_menu_window(&app_handle) {
+      #[cfg(target_os = "windows")]
+      if let Err(err) = initialize_menu_window(app.handle()) {
         eprintln!("Failed to initialize menu: {}", err);
       }
 
@@ -86,9 +100,13 @@ async fn main() -> anyhow::Result<()> {
     .invoke_handler(tauri::generate_handler![
       commands::widget_configs,
       commands::widget_states,
+      commands::widget_state,
       commands::start_widget,
       commands::start_preset,
       commands::stop_preset,
+      commands::set_widget_visibility,
+      commands::set_preset_visibility,
+      commands::reveal_widget,
       commands::update_widget_config,
       commands::listen_provider,
       commands::unlisten_provider,
@@ -96,19 +114,38 @@ async fn main() -> anyhow::Result<()> {
       commands::set_always_on_top,
       commands::set_skip_taskbar,
       commands::shell_exec,
+      commands::shell_cancel,
       commands::shell_spawn,
       commands::shell_write,
+      commands::shell_resize,
       commands::shell_kill,
+      commands::bus_publish,
+      commands::bus_subscribe,
+      commands::bus_unsubscribe,
+      #[cfg(target_os = "windows")]
       commands::set_foreground_window,
+      #[cfg(target_os = "windows")]
       commands::show_menu,
+      #[cfg(target_os = "windows")]
       commands::resize_menu,
+      #[cfg(target_os = "windows")]
       commands::hide_menu
     ])
     .build(tauri::generate_context!())?;
 
-  app.run(|_app, event| {
-     if let RunEvent::ExitRequested { api, .. } = &event {
-      api.prevent_exit();
+  app.run(|app, event| {
+    if let RunEvent::ExitRequested { api, code, .. } = &event {
+      match code {
+        // Exit was requested programmatically (e.g. via the tray menu).
+        // Widgets closed from here on are kept in the persisted session.
+        Some(_) => {
+          if let Some(widget_factory) = app.try_state::<Arc<WidgetFactory>>() {
+            widget_factory.set_exiting();
+          }
+        }
+        // Keep running in the background when all windows are closed.
+        None => api.prevent_exit(),
+      }
     }
   });
 
@@ -119,7 +156,10 @@ async fn main() -> anyhow::Result<()> {
 fn output_query(app: &tauri::App, args: QueryArgs) -> anyhow::Result<()> {
   match args {
     QueryArgs::Monitors => {
-      let monitors = MonitorState::new(&app.handle());
+      let monitors = MonitorState::new(
+        &app.handle(),
+        RuntimeSettings::default().monitor_poll_interval(),
+      );
       cli::print_and_exit(monitors.output_str());
       Ok(())
     }
@@ -128,9 +168,7 @@ fn output_query(app: &tauri::App, args: QueryArgs) -> anyhow::Result<()> {
 
 /// Starts EdgeBar - either with a specific widget or all widgets.
 async fn start_app(app: &mut tauri::App, cli: Cli) -> anyhow::Result<()> {
-  tracing_subscriber::fmt()
-    .with_env_filter(EnvFilter::from_env("LOG_LEVEL").add_directive(LevelFilter::INFO.into()))
-    .init();
+  let log_filter_handle = setup_logging();
 
   let config_dir_override = match cli.command() {
     CliCommand::Startup(args) => args.config_dir,
@@ -142,9 +180,15 @@ async fn start_app(app: &mut tauri::App, cli: Cli) -> anyhow::Result<()> {
   app.manage(config.clone());
 
   // Initialize `MonitorState` in Tauri state.
-  let monitor_state = Arc::new(MonitorState::new(app.handle()));
+  let runtime_settings = config.runtime_settings().await;
+  let monitor_state = Arc::new(MonitorState::new(
+    app.handle(),
+    runtime_settings.monitor_poll_interval(),
+  ));
   app.manage(monitor_state.clone());
 
+  apply_runtime_settings(&runtime_settings, &log_filter_handle, &monitor_state)?;
+
   // Initialize `WidgetFactory` in Tauri state.
   let widget_factory = Arc::new(WidgetFactory::new(
     app.handle(),
@@ -158,7 +202,7 @@ async fn start_app(app: &mut tauri::App, cli: Cli) -> anyhow::Result<()> {
   // guaranteed to be one of the open commands here.
   setup_single_instance(app, widget_factory.clone())?;
 
-  setup_asset_server();
+  setup_asset_server(runtime_settings.asset_server_port);
 
   // Allow assets to be resolved from the config directory.
   app
@@ -166,7 +210,9 @@ async fn start_app(app: &mut tauri::App, cli: Cli) -> anyhow::Result<()> {
     .allow_directory(&config.config_dir, true)?;
 
   app.manage(ShellState::new(app.handle(), widget_factory.clone()));
+  app.manage(MessageBus::new(app.handle(), widget_factory.clone()));
   app.handle().plugin(tauri_plugin_dialog::init())?;
+  setup_asset_server_capability(app, runtime_settings.asset_server_port)?;
 
   // Initialize `ProviderManager` in Tauri state.
   let (manager, emit_rx) = ProviderManager::new(app.handle());
@@ -178,8 +224,12 @@ async fn start_app(app: &mut tauri::App, cli: Cli) -> anyhow::Result<()> {
   // Add application icon to system tray.
   let tray = SysTray::new(app.handle()).await?;
 
+  // Hot-apply settings that are edited on disk.
+  config.clone().watch_settings();
+
   listen_events(
     app.handle(),
+    log_filter_handle,
     config,
     monitor_state,
     widget_factory,
@@ -191,8 +241,52 @@ async fn start_app(app: &mut tauri::App, cli: Cli) -> anyhow::Result<()> {
   Ok(())
 }
 
+/// Sets up logging to stdout.
+///
+/// Returns a handle for updating the log filter once settings are read.
+fn setup_logging() -> LogFilterHandle {
+  let (filter_layer, log_filter_handle) = reload::Layer::new(log_filter(None));
+
+  tracing_subscriber::registry()
+    .with(filter_layer)
+    .with(fmt::layer())
+    .init();
+
+  log_filter_handle
+}
+
+/// Creates a log filter from the `LOG_LEVEL` environment variable, or
+/// otherwise from the given `logLevel` setting. Defaults to `info`.
+fn log_filter(log_level: Option<&str>) -> EnvFilter {
+  let directives = env::var("LOG_LEVEL")
+    .ok()
+    .or_else(|| log_level.map(String::from))
+    .unwrap_or_default();
+
+  EnvFilter::builder()
+    .with_default_directive(LevelFilter::INFO.into())
+    .parse_lossy(directives)
+}
+
+/// Applies runtime settings that can be changed without a restart.
+fn apply_runtime_settings(
+  settings: &RuntimeSettings,
+  log_filter_handle: &LogFilterHandle,
+  monitor_state: &MonitorState,
+) -> anyhow::Result<()> {
+  log_filter_handle.reload(log_filter(settings.log_level.as_deref()))?;
+  monitor_state.set_poll_interval(settings.monitor_poll_interval());
+  monitor_state.set_aliases(settings.monitor_aliases.clone());
+
+  #[cfg(target_os = "windows")]
+  window_util::Window::set_ignored_titles(settings.ignored_window_titles.clone());
+
+  Ok(())
+}
+
 fn listen_events(
   app_handle: &AppHandle,
+  log_filter_handle: LogFilterHandle,
   config: Arc<Config>,
   monitor_state: Arc<MonitorState>,
   widget_factory: Arc<WidgetFactory>,
@@ -206,6 +300,7 @@ fn listen_events(
   let mut settings_change_rx = config.settings_change_tx.subscribe();
   let mut monitors_change_rx = monitor_state.change_tx.subscribe();
   let mut widget_configs_change_rx = config.widget_configs_change_tx.subscribe();
+  let mut widget_visibility_change_rx = widget_factory.visibility_change_tx.subscribe();
 
   task::spawn(async move {
     loop {
@@ -216,35 +311,45 @@ fn listen_events(
             error!("Error refreshing tray: {:?}", e);
           }
           let _ = app_handle.emit("widget-opened", widget_state);
-          Ok(())
+          widget_factory.save_session().await
         },
         Ok(widget_id) = widget_close_rx.recv() => {
           info!("Widget closed.");
           if let Err(e) = tray.refresh().await {
             error!("Error refreshing tray: {:?}", e);
           }
+          app_handle.state::<MessageBus>().unsubscribe_all(&widget_id).await;
           let _ = app_handle.emit("widget-closed", widget_id);
+          if let Err(e) = widget_factory.layout_docked_widgets().await {
+            error!("Error re-docking widgets: {:?}", e);
+          }
+          widget_factory.save_session().await
+        },
+        Ok(widget_state) = widget_visibility_change_rx.recv() => {
+          info!("Widget visibility changed.");
+          if let Err(e) = tray.refresh().await {
+            error!("Error refreshing tray: {:?}", e);
+          }
+          let _ = app_handle.emit("widget-visibility-changed", widget_state);
           Ok(())
         },
-        Ok(_) = settings_change_rx.recv() => {
+        Ok(settings) = settings_change_rx.recv() => {
           info!("Settings changed.");
           if let Err(e) = tray.refresh().await {
             error!("Error refreshing tray: {:?}", e);
           }
-          Ok(())
+          apply_runtime_settings(&settings.settings, &log_filter_handle, &monitor_state)
         },
         Ok(_) = monitors_change_rx.recv() => {
           info!("Monitors changed.");
-          /*
-          if let Err(e) = widget_factory.relaunch_all().await {
-            error!("Error relaunching widgets: {:?}", e);
+          if let Err(e) = widget_factory.reposition_all().await {
+            error!("Error repositioning widgets: {:?}", e);
           }
-          */
           Ok(())
         },
         Ok(changed_configs) = widget_configs_change_rx.recv() => {
           info!("Widget configs changed.");
-          if let Err(e) = widget_factory.relaunch_by_paths(&changed_configs.keys().cloned().collect()).await {
+          if let Err(e) = widget_factory.relaunch_by_configs(&changed_configs).await {
             error!("Error reconfiguring widgets: {:?}", e);
           }
           Ok(())
@@ -296,32 +401,53 @@ fn setup_single_instance(
   Ok(())
 }
 
+/// Grants the `widget` capability to widgets served from a non-default
+/// asset server port.
+///
+/// The bundled capability only allows the default port, such that other
+/// local servers are not given access to IPC.
+fn setup_asset_server_capability(app: &tauri::App, port: u16) -> anyhow::Result<()> {
+  if port == DEFAULT_ASSET_SERVER_PORT {
+    return Ok(());
+  }
+
+  let mut capability =
+    serde_json::from_str::<serde_json::Value>(include_str!("../capabilities/widget.json"))?;
+
+  capability["identifier"] = "widget-asset-server".into();
+  capability["remote"] = serde_json::json!({ "urls": [format!("http://127.0.0.1:{}", port)] });
+
+  app.add_capability(capability.to_string())?;
+
+  Ok(())
+}
+
 /// Opens widgets based on CLI command.
 async fn open_widgets_by_cli_command(
   cli: Cli,
   widget_factory: Arc<WidgetFactory>,
 ) -> anyhow::Result<()> {
   let res = match cli.command() {
-    CliCommand::StartWidget(args) => {
-      widget_factory
-        .start_widget(
-          &args.config_path,
-          &WidgetOpenOptions::Standalone(WidgetPlacement {
-            anchor: args.anchor,
-            offset_x: args.offset_x,
-            offset_y: args.offset_y,
-            width: args.width,
-            height: args.height,
-            monitor_selection: match args.monitor_type {
-              MonitorType::All => MonitorSelection::All,
-              MonitorType::Primary => MonitorSelection::Primary,
-              MonitorType::Secondary => MonitorSelection::Secondary,
-            },
-            dock_to_edge: Default::default(),
-          }),
-        )
-        .await
-    }
+    CliCommand::StartWidget(args) => match args.monitor_selection() {
+      Ok(monitor_selection) => {
+        widget_factory
+          .start_widget(
+            &args.config_path,
+            &WidgetOpenOptions::Standalone(WidgetPlacement {
+              anchor: args.anchor,
+              offset_x: args.offset_x,
+              offset_y: args.offset_y,
+              width: args.width,
+              height: args.height,
+              monitor_selection,
+              dock_to_edge: Default::default(),
+              overrides: vec![],
+            }),
+          )
+          .await
+      }
+      Err(err) => Err(err),
+    },
     CliCommand::StartWidgetPreset(args) => {
       widget_factory
         .start_widget(
@@ -330,6 +456,17 @@ async fn open_widgets_by_cli_command(
         )
         .await
     }
+    CliCommand::Show(args) => {
+      set_widget_visibility(&widget_factory, args, VisibilityAction::Show).await
+    }
+    CliCommand::Hide(args) => {
+      set_widget_visibility(&widget_factory, args, VisibilityAction::Hide).await
+    }
+    CliCommand::Toggle(args) => {
+      set_widget_visibility(&widget_factory, args, VisibilityAction::Toggle).await
+    }
+    CliCommand::Reveal(args) => reveal_widget(&widget_factory, args).await,
+    CliCommand::Profile(args) => widget_factory.switch_profile(&args.name).await,
     CliCommand::Startup(_) | CliCommand::Empty => widget_factory.startup().await,
     _ => unreachable!(),
   };
@@ -339,4 +476,36 @@ async fn open_widgets_by_cli_command(
   }
 
   Ok(())
-}
\ No newline at end of file
+}
+
+/// Shows, hides or toggles widgets based on CLI arguments.
+async fn set_widget_visibility(
+  widget_factory: &WidgetFactory,
+  args: WidgetVisibilityArgs,
+  action: VisibilityAction,
+) -> anyhow::Result<()> {
+  match (args.id, args.config_path, args.preset_name) {
+    (Some(id), _, _) => widget_factory.set_visibility_by_id(&id, action).await,
+    (None, Some(config_path), Some(preset_name)) => {
+      widget_factory
+        .set_visibility_by_preset(&config_path, &preset_name, action)
+        .await
+    }
+    _ => anyhow::bail!("Either a widget ID or a config path and preset is required."),
+  }
+}
+
+/// Reveals or hides auto-hiding widgets based on CLI arguments.
+async fn reveal_widget(widget_factory: &WidgetFactory, args: RevealArgs) -> anyhow::Result<()> {
+  let widget = args.widget;
+
+  match (widget.id, widget.config_path, widget.preset_name) {
+    (Some(id), _, _) => widget_factory.reveal_by_id(&id, args.action).await,
+    (None, Some(config_path), Some(preset_name)) => {
+      widget_factory
+        .reveal_by_preset(&config_path, &preset_name, args.action)
+        .await
+    }
+    _ => anyhow::bail!("Either a widget ID or a config path and preset is required."),
+  }
+}
@@ -0,0 +1,288 @@
+use std::{
+  collections::{HashMap, HashSet},
+  sync::Arc,
+};
+
+use anyhow::bail;
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::Mutex;
+
+use crate::widget_factory::WidgetFactory;
+
+/// Message sent between widgets over the message bus.
+///
+/// Sent to subscribed widgets via the `bus-message` event.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BusMessage {
+  /// Topic that the message was published to.
+  topic: String,
+
+  /// Arbitrary JSON payload.
+  payload: Value,
+
+  /// ID of the widget that published the message.
+  sender_id: String,
+
+  /// Whether the message is a retained message that was published prior
+  /// to subscribing.
+  retained: bool,
+}
+
+/// Subscriptions and retained messages of the message bus.
+#[derive(Debug, Default)]
+struct Topics {
+  /// Map of topics to the labels of windows subscribed to them.
+  subscriptions: HashMap<String, HashSet<String>>,
+
+  /// Last retained message per topic.
+  retained_messages: HashMap<String, BusMessage>,
+}
+
+impl Topics {
+  /// Stores the message if `retain` is set, or clears the retained
+  /// message if its payload is `null`.
+  ///
+  /// Returns the labels of windows subscribed to the message's topic.
+  fn publish(&mut self, message: &BusMessage, retain: bool) -> HashSet<String> {
+    if retain {
+      match message.payload.is_null() {
+        true => self.retained_messages.remove(&message.topic),
+        false => self.retained_messages.insert(
+          message.topic.clone(),
+          BusMessage {
+            retained: true,
+            ..message.clone()
+          },
+        ),
+      };
+    }
+
+    self
+      .subscriptions
+      .get(&message.topic)
+      .cloned()
+      .unwrap_or_default()
+  }
+
+  /// Subscribes a window to a topic.
+  ///
+  /// Returns the retained message for the topic, if any.
+  fn subscribe(&mut self, window_label: &str, topic: &str) -> Option<BusMessage> {
+    self
+      .subscriptions
+      .entry(topic.to_string())
+      .or_default()
+      .insert(window_label.to_string());
+
+    self.retained_messages.get(topic).cloned()
+  }
+
+  /// Unsubscribes a window from a topic.
+  fn unsubscribe(&mut self, window_label: &str, topic: &str) {
+    if let Some(labels) = self.subscriptions.get_mut(topic) {
+      labels.remove(window_label);
+
+      if labels.is_empty() {
+        self.subscriptions.remove(topic);
+      }
+    }
+  }
+
+  /// Removes all subscriptions of a window.
+  fn unsubscribe_all(&mut self, window_label: &str) {
+    for labels in self.subscriptions.values_mut() {
+      labels.remove(window_label);
+    }
+
+    self.subscriptions.retain(|_, labels| !labels.is_empty());
+  }
+}
+
+/// Routes messages between widgets by topic.
+#[derive(Debug)]
+pub struct MessageBus {
+  app_handle: AppHandle,
+
+  topics: Mutex<Topics>,
+
+  widget_factory: Arc<WidgetFactory>,
+}
+
+impl MessageBus {
+  /// Creates a new `MessageBus` instance.
+  pub fn new(app_handle: &AppHandle, widget_factory: Arc<WidgetFactory>) -> Self {
+    Self {
+      app_handle: app_handle.clone(),
+      topics: Mutex::new(Topics::default()),
+      widget_factory,
+    }
+  }
+
+  /// Publishes a message to all windows subscribed to the topic.
+  ///
+  /// If `retain` is set, the message is stored and delivered to future
+  /// subscribers. Publishing a retained `null` payload clears the
+  /// retained message.
+  pub async fn publish(
+    &self,
+    window_label: &str,
+    topic: &str,
+    payload: Value,
+    retain: bool,
+  ) -> anyhow::Result<()> {
+    let sender_id = self.check_topic_privilege(window_label, topic).await?;
+
+    let message = BusMessage {
+      topic: topic.to_string(),
+      payload,
+      sender_id,
+      retained: false,
+    };
+
+    let subscribers = self.topics.lock().await.publish(&message, retain);
+
+    for label in subscribers {
+      self.app_handle.emit_to(label, "bus-message", &message)?;
+    }
+
+    Ok(())
+  }
+
+  /// Subscribes a window to a topic.
+  ///
+  /// Returns the retained message for the topic, if any.
+  pub async fn subscribe(
+    &self,
+    window_label: &str,
+    topic: &str,
+  ) -> anyhow::Result<Option<BusMessage>> {
+    self.check_topic_privilege(window_label, topic).await?;
+
+    Ok(self.topics.lock().await.subscribe(window_label, topic))
+  }
+
+  /// Unsubscribes a window from a topic.
+  pub async fn unsubscribe(&self, window_label: &str, topic: &str) {
+    self.topics.lock().await.unsubscribe(window_label, topic);
+  }
+
+  /// Removes all subscriptions of a window (e.g. when it is closed).
+  pub async fn unsubscribe_all(&self, window_label: &str) {
+    self.topics.lock().await.unsubscribe_all(window_label);
+  }
+
+  /// Validates whether a window has privilege to use the given topic.
+  ///
+  /// Returns the ID of the widget that the window acts on behalf of.
+  async fn check_topic_privilege(&self, window_label: &str, topic: &str) -> anyhow::Result<String> {
+    if topic.is_empty() {
+      bail!("Topic cannot be empty.");
+    }
+
+    let Some(widget) = self
+      .widget_factory
+      .state_by_window_label(window_label)
+      .await
+    else {
+      bail!("Widget with ID '{window_label}' not found.");
+    };
+
+    if !is_topic_allowed(&widget.config.privileges.topics, topic) {
+      bail!("Topic '{topic}' is not allowed. Check widget's topic privileges.");
+    }
+
+    Ok(widget.id)
+  }
+}
+
+/// Whether the topic matches any of the given patterns.
+///
+/// A trailing `*` in a pattern matches any suffix (e.g. `media/*`
+/// matches `media/playing`).
+fn is_topic_allowed(patterns: &[String], topic: &str) -> bool {
+  patterns
+    .iter()
+    .any(|pattern| match pattern.strip_suffix('*') {
+      Some(prefix) => topic.starts_with(prefix),
+      None => pattern == topic,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+
+  use super::*;
+
+  fn message(topic: &str, payload: Value) -> BusMessage {
+    BusMessage {
+      topic: topic.into(),
+      payload,
+      sender_id: "widget-sender".into(),
+      retained: false,
+    }
+  }
+
+  #[test]
+  fn test_is_topic_allowed() {
+    let patterns = vec!["media/*".to_string(), "clock".to_string()];
+
+    assert!(is_topic_allowed(&patterns, "media/playing"));
+    assert!(is_topic_allowed(&patterns, "media/"));
+    assert!(is_topic_allowed(&patterns, "clock"));
+    assert!(!is_topic_allowed(&patterns, "media"));
+    assert!(!is_topic_allowed(&patterns, "clock/tick"));
+    assert!(!is_topic_allowed(&[], "clock"));
+  }
+
+  #[test]
+  fn test_is_topic_allowed_wildcard_only_at_end() {
+    let patterns = vec!["*".to_string(), "a*b".to_string()];
+
+    assert!(is_topic_allowed(&patterns, "anything"));
+    assert!(!is_topic_allowed(&["a*b".to_string()], "axb"));
+    assert!(is_topic_allowed(&["a*b".to_string()], "a*b"));
+  }
+
+  #[test]
+  fn test_publish_returns_subscribers() {
+    let mut topics = Topics::default();
+    topics.subscribe("widget-a", "clock");
+    topics.subscribe("widget-b", "clock");
+    topics.subscribe("widget-b", "media");
+
+    let subscribers = topics.publish(&message("clock", json!(1)), false);
+    assert_eq!(
+      subscribers,
+      HashSet::from(["widget-a".to_string(), "widget-b".to_string()])
+    );
+
+    topics.unsubscribe_all("widget-b");
+    topics.unsubscribe("widget-a", "clock");
+    assert!(
+      topics
+        .publish(&message("clock", json!(1)), false)
+        .is_empty()
+    );
+    assert!(topics.subscriptions.is_empty());
+  }
+
+  #[test]
+  fn test_retained_message_delivered_on_subscribe() {
+    let mut topics = Topics::default();
+
+    topics.publish(&message("clock", json!("12:00")), true);
+    topics.publish(&message("clock", json!("12:01")), false);
+
+    let retained = topics.subscribe("widget-a", "clock").unwrap();
+    assert_eq!(retained.payload, json!("12:00"));
+    assert!(retained.retained);
+
+    // Retaining a `null` payload clears the retained message.
+    topics.publish(&message("clock", Value::Null), true);
+    assert!(topics.subscribe("widget-b", "clock").is_none());
+  }
+}
@@ -1,14 +1,21 @@
-use std::{sync::Arc, time::Duration};
+use std::{
+  collections::HashMap,
+  sync::{Arc, RwLock as StdRwLock},
+  time::Duration,
+};
 
 use serde::Serialize;
 use tauri::AppHandle;
 use tokio::{
-  sync::{broadcast, RwLock},
+  sync::{RwLock, broadcast, mpsc, watch},
   task,
 };
-use tracing::info;
+use tracing::{info, warn};
 
-use crate::config::MonitorSelection;
+use crate::{
+  common::EdidInfo,
+  config::{MonitorMatch, MonitorSelection},
+};
 
 #[derive(Debug)]
 pub struct MonitorState {
@@ -18,6 +25,13 @@ pub struct MonitorState {
 
   /// Available monitors sorted from left-to-right and top-to-bottom.
   monitors: Arc<RwLock<Vec<Monitor>>>,
+
+  /// Interval to poll for monitor changes at. Only used when no event
+  /// source for screen changes is available.
+  poll_interval_tx: watch::Sender<Duration>,
+
+  /// User-defined monitor aliases from the settings.
+  aliases: StdRwLock<HashMap<String, MonitorMatch>>,
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize)]
@@ -30,79 +44,308 @@ pub struct Monitor {
   pub width: u32,
   pub height: u32,
   pub scale_factor: f32,
+
+  /// Manufacturer ID from the monitor's EDID (e.g. `DEL`).
+  pub manufacturer: Option<String>,
+
+  /// Model name from the monitor's EDID.
+  pub model: Option<String>,
+
+  /// Serial number from the monitor's EDID.
+  pub serial: Option<String>,
+
+  /// Bounds of the monitor minus taskbars, panels and other reserved
+  /// space.
+  pub work_area: WorkArea,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkArea {
+  pub x: i32,
+  pub y: i32,
+  pub width: u32,
+  pub height: u32,
+}
+
+impl WorkArea {
+  /// Returns the overlapping area of both rectangles, or `None` if they
+  /// don't overlap.
+  #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+  fn intersection(&self, other: &WorkArea) -> Option<WorkArea> {
+    let left = self.x.max(other.x);
+    let top = self.y.max(other.y);
+    let right = (self.x + self.width as i32).min(other.x + other.width as i32);
+    let bottom = (self.y + self.height as i32).min(other.y + other.height as i32);
+
+    (right > left && bottom > top).then(|| WorkArea {
+      x: left,
+      y: top,
+      width: (right - left) as u32,
+      height: (bottom - top) as u32,
+    })
+  }
+}
+
+impl Monitor {
+  /// Whether both monitors refer to the same physical display, even if
+  /// its position, size or scale factor has changed.
+  ///
+  /// Compares EDID identities where available, since device names can
+  /// change between docks. Falls back to comparing names, and then
+  /// positions for unnamed monitors.
+  pub fn is_same_device(&self, other: &Monitor) -> bool {
+    if self.serial.is_some() && other.serial.is_some() {
+      return self.manufacturer == other.manufacturer
+        && self.model == other.model
+        && self.serial == other.serial;
+    }
+
+    match (&self.name, &other.name) {
+      (Some(name), Some(other_name)) => name == other_name,
+      _ => self.x == other.x && self.y == other.y,
+    }
+  }
+
+  /// Returns an identifier of the physical display that is stable
+  /// across launches and monitor rearrangements (see `is_same_device`).
+  ///
+  /// Example: `DEL-DELL U2720Q-8C1L2K3` or `DP-1`.
+  pub fn device_id(&self) -> String {
+    if let Some(serial) = &self.serial {
+      return format!(
+        "{}-{}-{}",
+        self.manufacturer.as_deref().unwrap_or_default(),
+        self.model.as_deref().unwrap_or_default(),
+        serial
+      );
+    }
+
+    match &self.name {
+      // Windows device names are prefixed with `\\.\`.
+      Some(name) => name
+        .trim_start_matches(|char: char| !char.is_ascii_alphanumeric())
+        .to_string(),
+      None => format!("{}_{}", self.x, self.y),
+    }
+  }
+
+  /// Whether the monitor's EDID identity matches all fields specified
+  /// in the given match.
+  pub fn matches(&self, monitor_match: &MonitorMatch) -> bool {
+    let fields = [
+      (&monitor_match.manufacturer, &self.manufacturer),
+      (&monitor_match.model, &self.model),
+      (&monitor_match.serial, &self.serial),
+    ];
+
+    let is_empty = fields.iter().all(|(expected, _)| expected.is_none());
+
+    !is_empty
+      && fields
+        .iter()
+        .all(|(expected, actual)| expected.is_none() || expected == actual)
+  }
+
+  /// Whether both monitors are equal, disregarding their work areas.
+  fn eq_ignoring_work_area(&self, other: &Monitor) -> bool {
+    let without_work_area = |monitor: &Monitor| Monitor {
+      work_area: WorkArea::default(),
+      ..monitor.clone()
+    };
+
+    without_work_area(self) == without_work_area(other)
+  }
 }
 
 impl MonitorState {
   /// Creates a new `MonitorState` instance.
-  pub fn new(app_handle: &AppHandle) -> Self {
+  pub fn new(app_handle: &AppHandle, poll_interval: Duration) -> Self {
     let (change_tx, _change_rx) = broadcast::channel(16);
+    let (poll_interval_tx, poll_interval_rx) = watch::channel(poll_interval);
 
     let monitors = Arc::new(RwLock::new(Self::available_monitors(app_handle)));
 
-    Self::listen_changes(app_handle.clone(), monitors.clone(), change_tx.clone());
+    Self::listen_changes(
+      app_handle.clone(),
+      monitors.clone(),
+      change_tx.clone(),
+      poll_interval_rx,
+    );
 
     Self {
       monitors,
       _change_rx,
       change_tx,
+      poll_interval_tx,
+      aliases: StdRwLock::new(HashMap::new()),
     }
   }
 
+  /// Updates the user-defined monitor aliases.
+  pub fn set_aliases(&self, aliases: HashMap<String, MonitorMatch>) {
+    *self.aliases.write().unwrap() = aliases;
+  }
+
+  /// Updates the interval to poll for monitor changes at.
+  pub fn set_poll_interval(&self, poll_interval: Duration) {
+    self.poll_interval_tx.send_replace(poll_interval);
+  }
+
   /// Listens for display setting changes.
   ///
-  /// Updates monitor state on scaling changes, monitor connections, and
-  /// monitor disconnections. Does not update on working area changes.
+  /// Updates monitor state on scaling changes, monitor connections,
+  /// monitor disconnections, and work area changes. Changes are detected
+  /// via screen change events where available (RandR on X11), and by
+  /// polling otherwise.
+  ///
+  /// Work area changes alone are not broadcast, since they are commonly
+  /// caused by docking widgets.
   fn listen_changes(
     app_handle: AppHandle,
     monitors: Arc<RwLock<Vec<Monitor>>>,
     change_tx: broadcast::Sender<Vec<Monitor>>,
+    mut poll_interval_rx: watch::Receiver<Duration>,
   ) {
     task::spawn(async move {
-      let mut interval = tokio::time::interval(Duration::from_secs(4));
-
-      interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+      let mut interval = Self::poll_timer(*poll_interval_rx.borrow());
+      let mut screen_events = Self::screen_change_events();
 
       loop {
-        interval.tick().await;
+        tokio::select! {
+          _ = interval.tick(), if screen_events.is_none() => {},
+          Ok(_) = poll_interval_rx.changed() => {
+            interval = Self::poll_timer(*poll_interval_rx.borrow_and_update());
+            continue;
+          }
+          event = Self::next_screen_change(&mut screen_events), if screen_events.is_some() => {
+            if event.is_none() {
+              warn!("Screen change events ended. Falling back to polling.");
+              screen_events = None;
+              continue;
+            }
+
+            // Changes tend to arrive in bursts (e.g. one per output), so
+            // wait for them to settle before reading the new state.
+            tokio::time::sleep(Duration::from_millis(100)).await;
+
+            if let Some(screen_events) = screen_events.as_mut() {
+              while screen_events.try_recv().is_ok() {}
+            }
+          }
+        }
+
         let new_monitors = Self::available_monitors(&app_handle);
 
-        let should_update = {
+        let (has_changed, has_work_area_changed) = {
           let current_monitors = monitors.read().await;
-          *current_monitors != new_monitors
+
+          let has_changed = current_monitors.len() != new_monitors.len()
+            || current_monitors
+              .iter()
+              .zip(&new_monitors)
+              .any(|(current, new)| !current.eq_ignoring_work_area(new));
+
+          (has_changed, *current_monitors != new_monitors)
         };
 
-        if should_update {
-          info!("Detected change in monitors.");
+        if has_work_area_changed {
           *monitors.write().await = new_monitors.clone();
+        }
+
+        if has_changed {
+          info!("Detected change in monitors.");
           let _ = change_tx.send(new_monitors);
+        } else if has_work_area_changed {
+          info!("Detected change in monitor work areas.");
         }
       }
     });
   }
 
+  /// Subscribes to screen change events from the display server.
+  ///
+  /// Returns `None` if no event source is available, in which case
+  /// monitors are polled for changes instead.
+  #[cfg(target_os = "linux")]
+  fn screen_change_events() -> Option<mpsc::UnboundedReceiver<()>> {
+    match crate::common::linux::listen_screen_changes() {
+      Ok(screen_change_rx) => Some(screen_change_rx),
+      Err(err) => {
+        info!("Polling for monitor changes: {}", err);
+        None
+      }
+    }
+  }
+
+  /// Subscribes to screen change events from the display server.
+  ///
+  /// Returns `None` if no event source is available, in which case
+  /// monitors are polled for changes instead.
+  #[cfg(not(target_os = "linux"))]
+  fn screen_change_events() -> Option<mpsc::UnboundedReceiver<()>> {
+    None
+  }
+
+  /// Waits for the next screen change event.
+  ///
+  /// Returns `None` if there is no event source or it has closed.
+  async fn next_screen_change(
+    screen_change_rx: &mut Option<mpsc::UnboundedReceiver<()>>,
+  ) -> Option<()> {
+    screen_change_rx.as_mut()?.recv().await
+  }
+
+  /// Creates a timer that ticks at the given poll interval.
+  fn poll_timer(poll_interval: Duration) -> tokio::time::Interval {
+    // Tokio panics on a zero-length interval, so clamp to a sane minimum.
+    let mut interval = tokio::time::interval(poll_interval.max(Duration::from_millis(100)));
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    interval
+  }
+
   /// Gets available monitors on the system.
   ///
   /// Returns a vector of `Monitor` instances sorted from left-to-right and
   /// top-to-bottom.
   fn available_monitors(app_handle: &AppHandle) -> Vec<Monitor> {
     let primary_monitor = app_handle.primary_monitor().unwrap_or(None);
+    let edid_infos = Self::edid_infos();
+    let desktop_work_area = Self::desktop_work_area();
 
     let mut monitors = app_handle
       .available_monitors()
       .map(|monitors| {
         monitors
           .into_iter()
-          .map(|monitor| Monitor {
-            name: monitor.name().cloned(),
-            is_primary: primary_monitor
-              .as_ref()
-              .map(|m| m.name() == monitor.name())
-              .unwrap_or(false),
-            x: monitor.position().x,
-            y: monitor.position().y,
-            width: monitor.size().width,
-            height: monitor.size().height,
-            scale_factor: monitor.scale_factor() as f32,
+          .map(|monitor| {
+            let edid_info = monitor
+              .name()
+              .and_then(|name| Self::edid_info_by_name(&edid_infos, name));
+
+            let bounds = WorkArea {
+              x: monitor.position().x,
+              y: monitor.position().y,
+              width: monitor.size().width,
+              height: monitor.size().height,
+            };
+
+            Monitor {
+              name: monitor.name().cloned(),
+              is_primary: primary_monitor
+                .as_ref()
+                .map(|m| m.name() == monitor.name())
+                .unwrap_or(false),
+              x: monitor.position().x,
+              y: monitor.position().y,
+              width: monitor.size().width,
+              height: monitor.size().height,
+              scale_factor: monitor.scale_factor() as f32,
+              manufacturer: edid_info.map(|info| info.manufacturer.clone()),
+              model: edid_info.map(|info| info.model.clone()),
+              serial: edid_info.and_then(|info| info.serial.clone()),
+              work_area: Self::work_area(&bounds, desktop_work_area.as_ref()),
+            }
           })
           .collect()
       })
@@ -120,6 +363,83 @@ impl MonitorState {
     monitors
   }
 
+  /// Reads the work area spanning all monitors, if the platform only
+  /// exposes it as a whole (i.e. `_NET_WORKAREA` on X11).
+  #[cfg(target_os = "linux")]
+  fn desktop_work_area() -> Option<WorkArea> {
+    crate::common::linux::read_work_area()
+      .inspect_err(|err| warn!("Failed to read work area: {}", err))
+      .ok()
+  }
+
+  /// Reads the work area spanning all monitors, if the platform only
+  /// exposes it as a whole (i.e. `_NET_WORKAREA` on X11).
+  #[cfg(not(target_os = "linux"))]
+  fn desktop_work_area() -> Option<WorkArea> {
+    None
+  }
+
+  /// Gets the work area of a monitor with the given bounds.
+  ///
+  /// Falls back to the monitor bounds if the work area is unavailable.
+  #[cfg(target_os = "linux")]
+  fn work_area(bounds: &WorkArea, desktop_work_area: Option<&WorkArea>) -> WorkArea {
+    desktop_work_area
+      .and_then(|work_area| bounds.intersection(work_area))
+      .unwrap_or_else(|| bounds.clone())
+  }
+
+  /// Gets the work area of a monitor with the given bounds.
+  ///
+  /// Falls back to the monitor bounds if the work area is unavailable.
+  #[cfg(target_os = "windows")]
+  fn work_area(bounds: &WorkArea, _desktop_work_area: Option<&WorkArea>) -> WorkArea {
+    crate::common::windows::monitor_work_area(
+      bounds.x + bounds.width as i32 / 2,
+      bounds.y + bounds.height as i32 / 2,
+    )
+    .unwrap_or_else(|_| bounds.clone())
+  }
+
+  /// Gets the work area of a monitor with the given bounds.
+  ///
+  /// Not yet supported on this platform, so the monitor bounds are used.
+  #[cfg(not(any(target_os = "linux", target_os = "windows")))]
+  fn work_area(bounds: &WorkArea, _desktop_work_area: Option<&WorkArea>) -> WorkArea {
+    bounds.clone()
+  }
+
+  /// Reads EDID info of connected displays, keyed by connector name.
+  #[cfg(target_os = "linux")]
+  fn edid_infos() -> HashMap<String, EdidInfo> {
+    crate::common::linux::read_drm_edids()
+  }
+
+  /// Reads EDID info of connected displays, keyed by connector name.
+  ///
+  /// Not yet supported on this platform.
+  #[cfg(not(target_os = "linux"))]
+  fn edid_infos() -> HashMap<String, EdidInfo> {
+    HashMap::new()
+  }
+
+  /// Finds the EDID info for a monitor by its name.
+  fn edid_info_by_name<'a>(
+    edid_infos: &'a HashMap<String, EdidInfo>,
+    monitor_name: &str,
+  ) -> Option<&'a EdidInfo> {
+    #[cfg(target_os = "linux")]
+    let is_same_name = |name: &str| crate::common::linux::is_same_connector(name, monitor_name);
+
+    #[cfg(not(target_os = "linux"))]
+    let is_same_name = |name: &str| name == monitor_name;
+
+    edid_infos
+      .iter()
+      .find(|(name, _)| is_same_name(name))
+      .map(|(_, edid_info)| edid_info)
+  }
+
   /// Returns a string representation of the monitors.
   pub fn output_str(&self) -> anyhow::Result<String> {
     let monitors = self.monitors.try_read()?;
@@ -147,6 +467,21 @@ impl MonitorState {
         .into_iter()
         .filter(|monitor| monitor.name.as_deref() == Some(name))
         .collect(),
+      MonitorSelection::Edid(monitor_match) => monitors
+        .into_iter()
+        .filter(|monitor| monitor.matches(monitor_match))
+        .collect(),
+      MonitorSelection::Alias(alias) => {
+        let Some(monitor_match) = self.aliases.read().unwrap().get(alias).cloned() else {
+          warn!("No monitor alias named '{}' in settings.", alias);
+          return vec![];
+        };
+
+        monitors
+          .into_iter()
+          .filter(|monitor| monitor.matches(&monitor_match))
+          .collect()
+      }
     }
   }
 }
@@ -1,10 +1,13 @@
+#[cfg(target_os = "windows")]
 mod audio;
 mod provider;
 mod provider_config;
 mod provider_function;
 mod provider_manager;
 mod provider_output;
+#[cfg(target_os = "windows")]
 mod systray;
+#[cfg(target_os = "windows")]
 mod window;
 
 pub use provider::*;
@@ -1,5 +1,6 @@
 use serde::Deserialize;
 
+#[cfg(target_os = "windows")]
 use super::{
   audio::AudioProviderConfig, systray::SystrayProviderConfig, window::WindowProviderConfig,
 };
@@ -7,7 +8,10 @@ use super::{
 #[derive(Deserialize, Debug)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum ProviderConfig {
+  #[cfg(target_os = "windows")]
   Audio(AudioProviderConfig),
+  #[cfg(target_os = "windows")]
   Systray(SystrayProviderConfig),
+  #[cfg(target_os = "windows")]
   Window(WindowProviderConfig),
 }
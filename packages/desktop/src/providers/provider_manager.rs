@@ -9,9 +9,11 @@ use tokio::{
 };
 use tracing::info;
 
+#[cfg(target_os = "windows")]
+use super::{audio::AudioProvider, systray::SystrayProvider, window::WindowProvider, Provider};
 use super::{
-  audio::AudioProvider, systray::SystrayProvider, window::WindowProvider, Provider, ProviderConfig,
-  ProviderFunction, ProviderFunctionResponse, ProviderFunctionResult, ProviderOutput, RuntimeType,
+  ProviderConfig, ProviderFunction, ProviderFunctionResponse, ProviderFunctionResult,
+  ProviderOutput, RuntimeType,
 };
 
 /// Common fields for a provider.
@@ -186,6 +188,7 @@ impl ProviderManager {
   }
 
   /// Creates a new provider instance.
+  #[cfg(target_os = "windows")]
   fn create_instance(
     &self,
     config: ProviderConfig,
@@ -232,6 +235,20 @@ impl ProviderManager {
     Ok((task_handle, runtime_type))
   }
 
+  /// Creates a new provider instance.
+  ///
+  /// No providers are available on this platform yet, so a config can
+  /// never be constructed.
+  #[cfg(not(target_os = "windows"))]
+  fn create_instance(
+    &self,
+    config: ProviderConfig,
+    _config_hash: String,
+    _common: CommonProviderState,
+  ) -> anyhow::Result<(task::JoinHandle<()>, RuntimeType)> {
+    match config {}
+  }
+
   /// Sends a function call through a channel to be executed by the
   /// provider.
   ///
@@ -1,8 +1,10 @@
 use serde::Serialize;
 
+#[cfg(target_os = "windows")]
 use super::{audio::AudioOutput, systray::SystrayOutput, window::WindowOutput};
 
 /// Implements `From<T>` for `ProviderOutput` for each given variant.
+#[cfg(target_os = "windows")]
 macro_rules! impl_provider_output {
   ($($variant:ident($type:ty)),* $(,)?) => {
     $(
@@ -18,11 +20,15 @@ macro_rules! impl_provider_output {
 #[derive(Debug, Clone, PartialEq, Serialize)]
 #[serde(untagged)]
 pub enum ProviderOutput {
+  #[cfg(target_os = "windows")]
   Audio(AudioOutput),
+  #[cfg(target_os = "windows")]
   Systray(SystrayOutput),
+  #[cfg(target_os = "windows")]
   Window(WindowOutput),
 }
 
+#[cfg(target_os = "windows")]
 impl_provider_output! {
   Audio(AudioOutput),
   Systray(SystrayOutput),
@@ -0,0 +1,82 @@
+use std::{fs, path::PathBuf};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  common::{read_and_parse_config, write_atomic},
+  widget_factory::WidgetOpenOptions,
+};
+
+/// Widgets that were open in a previous run of EdgeBar.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Session {
+  pub widgets: Vec<SessionWidget>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionWidget {
+  /// Relative path to the widget config within the config directory.
+  pub config_path: PathBuf,
+
+  /// How the widget was opened.
+  pub open_options: WidgetOpenOptions,
+}
+
+impl Session {
+  /// Reads the session file at the given path.
+  ///
+  /// Returns `None` if no session file exists, or if it is empty or has
+  /// no widgets.
+  pub fn read(path: &PathBuf) -> anyhow::Result<Option<Self>> {
+    let is_empty = fs::metadata(path).map_or(true, |metadata| metadata.len() == 0);
+
+    if is_empty {
+      return Ok(None);
+    }
+
+    let session = read_and_parse_config::<Self>(path)
+      .with_context(|| format!("Failed to read session file at '{}'.", path.display()))?;
+
+    Ok(Some(session).filter(|session| !session.widgets.is_empty()))
+  }
+
+  /// Writes the session to the given path.
+  pub fn write(&self, path: &PathBuf) -> anyhow::Result<()> {
+    write_atomic(path, &(serde_json::to_string_pretty(self)? + "\n"))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::common::temp_dir;
+
+  #[test]
+  fn test_read_empty_session() {
+    let dir = temp_dir();
+
+    let path = dir.join("session.json");
+    assert_eq!(Session::read(&path).unwrap(), None);
+
+    fs::write(&path, "").unwrap();
+    assert_eq!(Session::read(&path).unwrap(), None);
+
+    Session::default().write(&path).unwrap();
+    assert_eq!(Session::read(&path).unwrap(), None);
+
+    let session = Session {
+      widgets: vec![SessionWidget {
+        config_path: "starter/vanilla.edgebar.json".into(),
+        open_options: WidgetOpenOptions::Preset("default".into()),
+      }],
+    };
+
+    session.write(&path).unwrap();
+    assert_eq!(Session::read(&path).unwrap(), Some(session));
+
+    fs::remove_dir_all(dir).unwrap();
+  }
+}
@@ -1,11 +1,17 @@
 use std::{
   collections::HashMap,
-  sync::{Arc, Mutex},
+  future,
+  sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc, Mutex,
+  },
 };
 
 use anyhow::{bail, Context};
 use serde::{Deserialize, Serialize};
-use shell_util::{Buffer, ChildProcessEvent, CommandOptions, ProcessId, Shell, ShellExecOutput};
+use shell_util::{
+  Buffer, ChildProcessEvent, CommandOptions, ProcessId, PtyOptions, Shell, ShellExecOutput,
+};
 use tauri::{AppHandle, Emitter};
 use tokio::sync::{mpsc, oneshot};
 
@@ -15,10 +21,20 @@ use crate::widget_factory::WidgetFactory;
 #[derive(Debug)]
 pub struct ProcessHandle {
   write_tx: mpsc::UnboundedSender<Buffer>,
+  resize_tx: mpsc::UnboundedSender<PtyOptions>,
   kill_tx: oneshot::Sender<()>,
   _event_task: tokio::task::JoinHandle<()>,
 }
 
+/// Handle for cancelling an in-flight `exec` call.
+#[derive(Debug)]
+struct ExecHandle {
+  /// Sequence number of the call, such that the handle isn't confused
+  /// with that of a later call with the same exec ID.
+  sequence: u64,
+  cancel_tx: oneshot::Sender<()>,
+}
+
 /// Payload for events emitted by spawned child processes.
 ///
 /// Sent to the client via the `shell-emit` event.
@@ -60,6 +76,13 @@ impl From<ShellCommandArgs> for String {
 pub struct ShellState {
   app_handle: AppHandle,
   children: Arc<Mutex<HashMap<ProcessId, ProcessHandle>>>,
+
+  /// Handles of in-flight `exec` calls, keyed by widget ID and exec ID.
+  execs: Arc<Mutex<HashMap<(String, String), ExecHandle>>>,
+
+  /// Number of `exec` calls with an exec ID. Used as sequence number.
+  exec_count: AtomicU64,
+
   widget_factory: Arc<WidgetFactory>,
 }
 
@@ -68,6 +91,8 @@ impl ShellState {
   pub fn new(app_handle: &AppHandle, widget_factory: Arc<WidgetFactory>) -> Self {
     Self {
       children: Arc::new(Mutex::new(HashMap::new())),
+      execs: Arc::new(Mutex::new(HashMap::new())),
+      exec_count: AtomicU64::new(0),
       app_handle: app_handle.clone(),
       widget_factory,
     }
@@ -76,21 +101,83 @@ impl ShellState {
   /// Executes a command as a child process.
   ///
   /// Validates widget's shell privileges before executing the command.
+  /// If an `exec_id` is given, the command can be cancelled through
+  /// `ShellState::cancel` while it is running. Errors if an `exec` call
+  /// with the same `exec_id` is already running for the widget.
   pub async fn exec(
     &self,
     widget_id: &str,
     program: &str,
     args: ShellCommandArgs,
     options: &CommandOptions,
+    exec_id: Option<String>,
   ) -> anyhow::Result<ShellExecOutput> {
     self
       .check_shell_privilege(widget_id, program, args.clone())
       .await?;
 
     let args_vec: Vec<String> = args.into();
-    let output = Shell::exec(program, &args_vec, options).await?;
 
-    Ok(output)
+    let Some(exec_id) = exec_id else {
+      return Ok(Shell::exec(program, &args_vec, options).await?);
+    };
+
+    let key = (widget_id.to_string(), exec_id);
+    let sequence = self.exec_count.fetch_add(1, Ordering::Relaxed);
+    let (cancel_tx, cancel_rx) = oneshot::channel();
+
+    {
+      let mut execs = self.execs.lock().unwrap();
+
+      if execs.contains_key(&key) {
+        bail!("Exec with ID '{}' is already running.", key.1);
+      }
+
+      execs.insert(
+        key.clone(),
+        ExecHandle {
+          sequence,
+          cancel_tx,
+        },
+      );
+    }
+
+    // Only cancel on an explicit signal, not when the sender is dropped.
+    let output = Shell::exec_with_cancel(program, &args_vec, options, async {
+      if cancel_rx.await.is_err() {
+        future::pending::<()>().await;
+      }
+    })
+    .await;
+
+    // The entry is removed on cancel, after which another call might
+    // have reused the exec ID.
+    {
+      let mut execs = self.execs.lock().unwrap();
+
+      if execs
+        .get(&key)
+        .is_some_and(|handle| handle.sequence == sequence)
+      {
+        execs.remove(&key);
+      }
+    }
+
+    Ok(output?)
+  }
+
+  /// Cancels an in-flight `exec` call of a widget, killing its process.
+  pub fn cancel(&self, widget_id: &str, exec_id: &str) -> anyhow::Result<()> {
+    let key = (widget_id.to_string(), exec_id.to_string());
+
+    if let Some(handle) = self.execs.lock().unwrap().remove(&key) {
+      handle
+        .cancel_tx
+        .send(())
+        .map_err(|_| anyhow::anyhow!("Failed to send cancel command."))?;
+    }
+
+    Ok(())
   }
 
   /// Spawns a new child process.
@@ -114,8 +201,9 @@ impl ShellState {
     let widget_id = widget_id.to_string();
     let pid = child.pid();
 
-    // Create channels for write and kill signals.
+    // Create channels for write, resize and kill signals.
     let (write_tx, mut write_rx) = mpsc::unbounded_channel::<Buffer>();
+    let (resize_tx, mut resize_rx) = mpsc::unbounded_channel::<PtyOptions>();
     let (kill_tx, mut kill_rx) = oneshot::channel();
 
     // Set up event handling.
@@ -140,6 +228,16 @@ impl ShellState {
             }
           }
 
+          // Process resize requests.
+          Some(size) = resize_rx.recv() => {
+            if let Err(err) = child.resize(size) {
+              let _ = app_handle.emit_to(widget_id.clone(), "shell-emit", ShellEmission {
+                pid,
+                event: ChildProcessEvent::Error(format!("Resize error: {}", err)),
+              });
+            }
+          }
+
           // Kill the process when signal is received.
           _ = &mut kill_rx => {
             let _ = child.kill();
@@ -153,6 +251,7 @@ impl ShellState {
       pid,
       ProcessHandle {
         write_tx,
+        resize_tx,
         kill_tx,
         _event_task: event_task,
       },
@@ -173,6 +272,18 @@ impl ShellState {
     Ok(())
   }
 
+  /// Resizes the pseudo-terminal of a running process.
+  pub fn resize(&self, pid: ProcessId, size: PtyOptions) -> anyhow::Result<()> {
+    if let Some(handle) = self.children.lock().unwrap().get(&pid) {
+      handle
+        .resize_tx
+        .send(size)
+        .context("Failed to send resize command.")?;
+    }
+
+    Ok(())
+  }
+
   /// Terminates a running process.
   pub fn kill(&self, pid: ProcessId) -> anyhow::Result<()> {
     if let Some(handle) = self.children.lock().unwrap().remove(&pid) {
@@ -195,16 +306,9 @@ impl ShellState {
     program: &str,
     args: ShellCommandArgs,
   ) -> anyhow::Result<()> {
-    // Treat "dropdown" as if it were "macos"
-    let effective_widget_id = if widget_id == "macos" {
-      "widget-1"
-    } else {
-      widget_id
-    };
-
     let widget = self
       .widget_factory
-      .state_by_id(effective_widget_id)
+      .state_by_window_label(widget_id)
       .await
       .with_context(|| format!("Widget with ID '{widget_id}' not found."))?;
 
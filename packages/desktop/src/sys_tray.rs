@@ -1,23 +1,34 @@
-use std::str::FromStr;
+use std::{str::FromStr, sync::Arc};
 
 use anyhow::{bail, Context};
 use tauri::{
   image::Image,
-  menu::{Menu, MenuBuilder},
+  menu::{CheckMenuItemBuilder, Menu, MenuBuilder, SubmenuBuilder},
   tray::{TrayIcon, TrayIconBuilder},
-  AppHandle, Wry,
+  AppHandle, Manager, Wry,
 };
 use tokio::task;
 use tracing::{error, info};
 
+use crate::{
+  config::Config,
+  widget_factory::{VisibilityAction, WidgetFactory},
+};
+
 #[derive(Debug, Clone)]
 enum MenuEvent {
+  ToggleWidget(String),
+  SwitchProfile(String),
+  ReloadSettings,
   Exit,
 }
 
 impl ToString for MenuEvent {
   fn to_string(&self) -> String {
     match self {
+      MenuEvent::ToggleWidget(id) => format!("toggle_{}", id),
+      MenuEvent::SwitchProfile(name) => format!("profile_{}", name),
+      MenuEvent::ReloadSettings => "reload_settings".to_string(),
       MenuEvent::Exit => "exit".to_string(),
     }
   }
@@ -30,6 +41,9 @@ impl FromStr for MenuEvent {
     let parts: Vec<&str> = event.split('_').collect();
 
     match parts.as_slice() {
+      ["toggle", id @ ..] => Ok(Self::ToggleWidget(id.join("_"))),
+      ["profile", name @ ..] => Ok(Self::SwitchProfile(name.join("_"))),
+      ["reload", "settings"] => Ok(Self::ReloadSettings),
       ["exit"] => Ok(Self::Exit),
       _ => bail!("Invalid menu event: {}", event),
     }
@@ -88,7 +102,7 @@ impl SysTray {
   }
 
   /// Returns the image to use for the system tray icon.
-  fn icon_image(&self) -> anyhow::Result<Image> {
+  fn icon_image(&self) -> anyhow::Result<Image<'_>> {
     self
       .app_handle
       .default_window_icon()
@@ -98,7 +112,58 @@ impl SysTray {
 
   /// Creates and returns the main system tray menu.
   async fn create_tray_menu(&self) -> anyhow::Result<Menu<Wry>> {
-    let tray_menu = MenuBuilder::new(&self.app_handle)
+    let mut tray_menu = MenuBuilder::new(&self.app_handle);
+
+    let widget_factory = self.app_handle.state::<Arc<WidgetFactory>>();
+    let mut widget_states = widget_factory
+      .states()
+      .await
+      .into_values()
+      .collect::<Vec<_>>();
+
+    let config = self.app_handle.state::<Arc<Config>>();
+    let (profiles, active_profile) = config.profiles().await;
+
+    let has_submenus = !widget_states.is_empty() || !profiles.is_empty();
+
+    if !widget_states.is_empty() {
+      let mut widgets_menu = SubmenuBuilder::new(&self.app_handle, "Widgets");
+      widget_states.sort_by(|a, b| a.id.cmp(&b.id));
+
+      for state in widget_states {
+        widgets_menu = widgets_menu.item(
+          &CheckMenuItemBuilder::with_id(MenuEvent::ToggleWidget(state.id.clone()), &state.id)
+            .checked(state.is_visible)
+            .build(&self.app_handle)?,
+        );
+      }
+
+      tray_menu = tray_menu.item(&widgets_menu.build()?);
+    }
+
+    if !profiles.is_empty() {
+      let mut profiles_menu = SubmenuBuilder::new(&self.app_handle, "Profiles");
+
+      for profile in profiles {
+        let is_active = active_profile.as_ref() == Some(&profile);
+
+        profiles_menu = profiles_menu.item(
+          &CheckMenuItemBuilder::with_id(MenuEvent::SwitchProfile(profile.clone()), &profile)
+            .checked(is_active)
+            .build(&self.app_handle)?,
+        );
+      }
+
+      tray_menu = tray_menu.item(&profiles_menu.build()?);
+    }
+
+    if has_submenus {
+      tray_menu = tray_menu.separator();
+    }
+
+    let tray_menu = tray_menu
+      .text(MenuEvent::ReloadSettings, "Reload settings")
+      .separator()
       .text(MenuEvent::Exit, "Exit")
       .build()?;
 
@@ -111,6 +176,20 @@ impl SysTray {
       info!("Received tray menu event: {:?}", event);
 
       let event_res: Result<(), anyhow::Error> = match event {
+        MenuEvent::ToggleWidget(id) => {
+          let widget_factory = app_handle.state::<Arc<WidgetFactory>>();
+          widget_factory
+            .set_visibility_by_id(&id, VisibilityAction::Toggle)
+            .await
+        }
+        MenuEvent::SwitchProfile(name) => {
+          let widget_factory = app_handle.state::<Arc<WidgetFactory>>();
+          widget_factory.switch_profile(&name).await
+        }
+        MenuEvent::ReloadSettings => {
+          let config = app_handle.state::<Arc<Config>>();
+          config.reload_settings().await
+        }
         MenuEvent::Exit => {
           app_handle.exit(0);
           Ok(())
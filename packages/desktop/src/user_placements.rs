@@ -0,0 +1,68 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  common::{LengthValue, read_and_parse_config, write_atomic},
+  config::WidgetPlacement,
+};
+
+/// Placements of widgets that were moved or resized by the user.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserPlacements {
+  /// Map of widget ID's to their adjusted placement.
+  pub widgets: BTreeMap<String, UserPlacement>,
+}
+
+/// Placement of a widget relative to its anchor-point. Takes precedence
+/// over the placement in the widget config.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserPlacement {
+  pub offset_x: LengthValue,
+
+  pub offset_y: LengthValue,
+
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub width: Option<LengthValue>,
+
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub height: Option<LengthValue>,
+}
+
+impl UserPlacement {
+  /// Applies the adjusted fields to the given placement.
+  pub fn apply(&self, placement: &mut WidgetPlacement) {
+    placement.offset_x = self.offset_x.clone();
+    placement.offset_y = self.offset_y.clone();
+
+    if let Some(width) = &self.width {
+      placement.width = width.clone();
+    }
+
+    if let Some(height) = &self.height {
+      placement.height = height.clone();
+    }
+  }
+}
+
+impl UserPlacements {
+  /// Reads the user placements file at the given path.
+  ///
+  /// Returns empty placements if no file exists.
+  pub fn read(path: &PathBuf) -> anyhow::Result<Self> {
+    if !path.exists() {
+      return Ok(Self::default());
+    }
+
+    read_and_parse_config(path)
+      .with_context(|| format!("Failed to read placements file at '{}'.", path.display()))
+  }
+
+  /// Writes the user placements to the given path.
+  pub fn write(&self, path: &PathBuf) -> anyhow::Result<()> {
+    write_atomic(path, &(serde_json::to_string_pretty(self)? + "\n"))
+  }
+}
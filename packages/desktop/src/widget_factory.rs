@@ -1,15 +1,19 @@
+#[cfg(target_os = "windows")]
+use std::collections::HashSet;
 use std::{
   collections::HashMap,
   path::PathBuf,
   sync::{
-    atomic::{AtomicU32, Ordering},
+    atomic::{AtomicBool, AtomicU32, Ordering},
     Arc,
   },
+  time::{Duration, Instant},
 };
 
 use anyhow::{bail, Context};
 use base64::prelude::*;
-use serde::Serialize;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
 use tauri::{
   self, path::BaseDirectory, AppHandle, Manager, PhysicalPosition, PhysicalSize, WebviewUrl,
   WebviewWindowBuilder, WindowEvent,
@@ -18,15 +22,42 @@ use tokio::{
   sync::{broadcast, Mutex},
   task,
 };
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
+#[cfg(target_os = "linux")]
+use crate::common::linux::{remove_dock_strut, WindowExtLinux};
+#[cfg(target_os = "windows")]
 use crate::common::windows::{remove_app_bar, WindowExtWindows};
 use crate::{
-  asset_server::create_init_url,
-  common::PathExt,
-  config::{AnchorPoint, Config, DockConfig, DockEdge, WidgetConfig, WidgetPlacement},
+  asset_server::{asset_server_port, create_init_url},
+  common::{content_hash, LengthValue, PathExt},
+  config::{
+    AnchorPoint, AutoHideConfig, Config, DockConfig, DockEdge, WidgetConfig, WidgetPlacement,
+  },
+  dock_layout::{stack_dock_slots, DockSlot},
   monitor_state::{Monitor, MonitorState},
+  session::{Session, SessionWidget},
+  user_placements::{UserPlacement, UserPlacements},
 };
+#[cfg(target_os = "windows")]
+use menu_util::MENU_WINDOW_LABEL;
+#[cfg(target_os = "windows")]
+use window_util::Window as UtilWindow;
+
+/// Interval to check the cursor position at for auto-hiding widgets.
+const AUTO_HIDE_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Number of frames to slide an auto-hiding widget in or out over.
+const AUTO_HIDE_ANIMATION_FRAMES: i32 = 10;
+
+/// Interval to re-check the foreground window at. Catches windows that
+/// enter or leave fullscreen without a change in foreground window.
+#[cfg(target_os = "windows")]
+const FULLSCREEN_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Delay after the last move or resize of a widget before its placement
+/// is persisted.
+const USER_PLACEMENT_SAVE_DELAY: Duration = Duration::from_millis(500);
 
 /// Manages the creation of EdgeBar widgets.
 #[derive(Debug)]
@@ -45,17 +76,38 @@ pub struct WidgetFactory {
 
   pub open_tx: broadcast::Sender<WidgetState>,
 
+  _visibility_change_rx: broadcast::Receiver<WidgetState>,
+
+  pub visibility_change_tx: broadcast::Sender<WidgetState>,
+
   /// Reference to `MonitorState`.
   monitor_state: Arc<MonitorState>,
 
   /// Running total of widgets created.
   ///
-  /// Used to generate unique widget ID's which are used as Tauri window
-  /// labels.
+  /// Used to distinguish between windows that reuse the same widget ID
+  /// (e.g. when a widget is relaunched).
   widget_count: Arc<AtomicU32>,
 
   /// Map of widget ID's to their states.
   widget_states: Arc<Mutex<HashMap<String, WidgetState>>>,
+
+  /// Whether the app is exiting. Widgets closed while exiting are kept
+  /// in the persisted session.
+  is_exiting: AtomicBool,
+
+  /// ID of the widget that last opened the dropdown menu. The menu
+  /// window acts on behalf of this widget.
+  menu_owner_id: Mutex<Option<String>>,
+
+  /// Map of widget ID's to the state of docked widgets that auto-hide.
+  auto_hide_states: Arc<Mutex<HashMap<String, AutoHideState>>>,
+
+  /// Placements of widgets that were moved or resized by the user.
+  user_placements: Arc<Mutex<UserPlacements>>,
+
+  /// Map of widget ID's to widgets that are stacked on a monitor edge.
+  docked_widgets: Arc<Mutex<HashMap<String, DockedWidget>>>,
 }
 
 #[derive(Serialize, Clone, Debug)]
@@ -63,12 +115,15 @@ pub struct WidgetFactory {
 pub struct WidgetState {
   /// Unique identifier for the widget.
   ///
-  /// Used as the Tauri window label.
+  /// Derived from the config path, preset and monitor (e.g.
+  /// `widget-starter_vanilla-default-DP-1`), such that it is stable across
+  /// launches. Used as the Tauri window label.
   pub id: String,
 
   /// Handle to the underlying Tauri window.
   ///
-  /// This is only available on Windows.
+  /// This is the HWND on Windows and the X11 window ID on Linux. Not
+  /// available under Wayland.
   pub window_handle: Option<isize>,
 
   /// User-defined config for the widget.
@@ -82,21 +137,217 @@ pub struct WidgetState {
 
   /// How the widget was opened.
   pub open_options: WidgetOpenOptions,
+
+  /// Monitor that the widget is placed on.
+  pub monitor: Monitor,
+
+  /// Whether the widget's window is currently shown.
+  pub is_visible: bool,
+
+  /// Sequence number of the underlying window.
+  #[serde(skip)]
+  instance: u32,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(rename_all = "snake_case")]
 pub enum WidgetOpenOptions {
   Standalone(WidgetPlacement),
   Preset(String),
 }
 
+/// Change to apply to the visibility of a widget.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize, ValueEnum)]
+#[clap(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum VisibilityAction {
+  Show,
+  Hide,
+  /// Hides the widget if visible, otherwise shows it.
+  Toggle,
+}
+
+/// Bounds of a widget window that is docked to a monitor edge.
+#[derive(Clone, Debug)]
+struct DockedBounds {
+  edge: DockEdge,
+  size: PhysicalSize<i32>,
+  position: PhysicalPosition<i32>,
+
+  /// Screen area reserved as app bar space.
+  reserve_size: PhysicalSize<i32>,
+  reserve_position: PhysicalPosition<i32>,
+}
+
+/// Space to reserve for a widget that is docked to a monitor edge.
+#[derive(Clone, Copy, Debug)]
+struct DockReservation {
+  edge: DockEdge,
+
+  /// Margin to reserve after the window.
+  window_margin: i32,
+
+  /// Length of the reserved space perpendicular to the edge.
+  reserved_length: i32,
+}
+
+/// A widget that is stacked with other widgets on a monitor edge.
+#[derive(Clone, Debug)]
+struct DockedWidget {
+  coordinates: WidgetCoordinates,
+  reservation: DockReservation,
+  instance: u32,
+
+  /// Offset from the monitor edge that the widget is docked at. `None`
+  /// if not yet docked.
+  offset: Option<i32>,
+
+  bounds: Option<DockedBounds>,
+}
+
+/// State of a docked widget that auto-hides.
+#[derive(Clone, Debug)]
+struct AutoHideState {
+  bounds: DockedBounds,
+  monitor: Monitor,
+
+  /// Position of the window while hidden, such that only the reveal
+  /// strip is left on-screen.
+  hidden_position: PhysicalPosition<i32>,
+
+  /// Length of the reveal strip in physical pixels.
+  reveal_strip: i32,
+
+  hide_delay: Duration,
+
+  is_hidden: bool,
+
+  /// Whether the widget was revealed via the CLI or IPC. Pinned widgets
+  /// aren't hidden when the cursor leaves them.
+  is_pinned: bool,
+
+  /// Sequence number of the underlying window.
+  instance: u32,
+}
+
+impl AutoHideState {
+  fn new(bounds: DockedBounds, monitor: Monitor, config: &AutoHideConfig, instance: u32) -> Self {
+    let window_length = match bounds.edge.is_horizontal() {
+      true => bounds.size.height,
+      false => bounds.size.width,
+    };
+
+    let reveal_strip = config
+      .reveal_strip
+      .to_px_in_viewport(
+        window_length,
+        monitor.width as i32,
+        monitor.height as i32,
+        monitor.scale_factor,
+      )
+      .clamp(0, window_length);
+
+    let monitor_right = monitor.x + monitor.width as i32;
+    let monitor_bottom = monitor.y + monitor.height as i32;
+
+    let hidden_position = match bounds.edge {
+      DockEdge::Top => PhysicalPosition::new(
+        bounds.position.x,
+        monitor.y + reveal_strip - bounds.size.height,
+      ),
+      DockEdge::Bottom => PhysicalPosition::new(bounds.position.x, monitor_bottom - reveal_strip),
+      DockEdge::Left => PhysicalPosition::new(
+        monitor.x + reveal_strip - bounds.size.width,
+        bounds.position.y,
+      ),
+      DockEdge::Right => PhysicalPosition::new(monitor_right - reveal_strip, bounds.position.y),
+    };
+
+    Self {
+      bounds,
+      monitor,
+      hidden_position,
+      reveal_strip,
+      hide_delay: config.hide_delay(),
+      is_hidden: false,
+      is_pinned: false,
+      instance,
+    }
+  }
+
+  /// Whether the cursor is over the revealed window.
+  fn is_hovered(&self, cursor: PhysicalPosition<i32>) -> bool {
+    let PhysicalPosition { x, y } = self.bounds.position;
+    let PhysicalSize { width, height } = self.bounds.size;
+
+    cursor.x >= x && cursor.x < x + width && cursor.y >= y && cursor.y < y + height
+  }
+
+  /// Whether the cursor touches the monitor edge alongside the hidden
+  /// window.
+  fn is_at_edge(&self, cursor: PhysicalPosition<i32>) -> bool {
+    // Always allow at least the outermost pixel row to trigger a reveal.
+    let strip = self.reveal_strip.max(1);
+    let monitor_right = self.monitor.x + self.monitor.width as i32;
+    let monitor_bottom = self.monitor.y + self.monitor.height as i32;
+
+    let PhysicalPosition { x, y } = self.bounds.position;
+    let PhysicalSize { width, height } = self.bounds.size;
+
+    let is_along_x = cursor.x >= x && cursor.x < x + width;
+    let is_along_y = cursor.y >= y && cursor.y < y + height;
+
+    // Distance from the cursor to the monitor edge.
+    let (is_alongside, distance) = match self.bounds.edge {
+      DockEdge::Top => (is_along_x, cursor.y - self.monitor.y),
+      DockEdge::Bottom => (is_along_x, monitor_bottom - 1 - cursor.y),
+      DockEdge::Left => (is_along_y, cursor.x - self.monitor.x),
+      DockEdge::Right => (is_along_y, monitor_right - 1 - cursor.x),
+    };
+
+    is_alongside && (0..strip).contains(&distance)
+  }
+}
+
+#[derive(Clone, Debug)]
 struct WidgetCoordinates {
   size: PhysicalSize<i32>,
   position: PhysicalPosition<i32>,
   offset: PhysicalPosition<i32>,
   anchor: AnchorPoint,
   monitor: Monitor,
+  /// Placement after merging in overrides for the monitor.
+  placement: WidgetPlacement,
+}
+
+impl WidgetState {
+  /// Gets the placement of the widget, either from its preset or from
+  /// the standalone open options.
+  fn placement(&self) -> Option<&WidgetPlacement> {
+    match &self.open_options {
+      WidgetOpenOptions::Standalone(placement) => Some(placement),
+      WidgetOpenOptions::Preset(name) => self
+        .config
+        .presets
+        .iter()
+        .find(|preset| preset.name == *name)
+        .map(|preset| &preset.placement),
+    }
+  }
+
+  /// Whether the widget's preset is set to hide while a fullscreen
+  /// window is in the foreground.
+  #[cfg(target_os = "windows")]
+  fn hides_on_fullscreen(&self) -> bool {
+    match &self.open_options {
+      WidgetOpenOptions::Standalone(_) => false,
+      WidgetOpenOptions::Preset(name) => self
+        .config
+        .presets
+        .iter()
+        .any(|preset| preset.name == *name && preset.hide_on_fullscreen),
+    }
+  }
 }
 
 impl WidgetCoordinates {
@@ -151,6 +402,24 @@ impl WidgetFactory {
   ) -> Self {
     let (open_tx, _open_rx) = broadcast::channel(16);
     let (close_tx, _close_rx) = broadcast::channel(16);
+    let (visibility_change_tx, _visibility_change_rx) = broadcast::channel(16);
+
+    let widget_states = Arc::new(Mutex::new(HashMap::new()));
+    let auto_hide_states = Arc::new(Mutex::new(HashMap::new()));
+    let docked_widgets = Arc::new(Mutex::new(HashMap::new()));
+
+    Self::listen_fullscreen(
+      app_handle.clone(),
+      widget_states.clone(),
+      auto_hide_states.clone(),
+      docked_widgets.clone(),
+    );
+
+    let user_placements =
+      UserPlacements::read(&config.user_placements_path()).unwrap_or_else(|err| {
+        warn!("Failed to read user placements: {:?}", err);
+        UserPlacements::default()
+      });
 
     Self {
       app_handle: app_handle.clone(),
@@ -159,19 +428,138 @@ impl WidgetFactory {
       config,
       _open_rx,
       open_tx,
+      _visibility_change_rx,
+      visibility_change_tx,
       monitor_state,
       widget_count: Arc::new(AtomicU32::new(0)),
-      widget_states: Arc::new(Mutex::new(HashMap::new())),
+      widget_states,
+      is_exiting: AtomicBool::new(false),
+      menu_owner_id: Mutex::new(None),
+      auto_hide_states,
+      user_placements: Arc::new(Mutex::new(user_placements)),
+      docked_widgets,
     }
   }
 
+  /// Listens for foreground window changes and hides widgets with
+  /// `hideOnFullscreen` while a fullscreen window covers their monitor.
+  ///
+  /// Widgets are shown again once the fullscreen window is no longer in
+  /// the foreground, unless they have since been hidden explicitly.
+  /// Docked widgets release their reserved space while hidden.
+  #[cfg(target_os = "windows")]
+  fn listen_fullscreen(
+    app_handle: AppHandle,
+    widget_states: Arc<Mutex<HashMap<String, WidgetState>>>,
+    auto_hide_states: Arc<Mutex<HashMap<String, AutoHideState>>>,
+    docked_widgets: Arc<Mutex<HashMap<String, DockedWidget>>>,
+  ) {
+    task::spawn(async move {
+      let Ok(mut foreground_events) = UtilWindow::new() else {
+        warn!("Failed to listen for foreground window changes.");
+        return;
+      };
+
+      let mut interval = tokio::time::interval(FULLSCREEN_POLL_INTERVAL);
+      interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+      // ID's of widgets that are hidden due to a fullscreen window.
+      let mut hidden_ids = HashSet::new();
+
+      loop {
+        let foreground = tokio::select! {
+          // Windows without a title or with an ignored title (e.g. many
+          // fullscreen games) are included, since only their bounds matter.
+          Some(event) = foreground_events.all_events() => event,
+          _ = interval.tick() => match UtilWindow::foreground() {
+            Some(event) => event,
+            None => continue,
+          },
+        };
+
+        let states = widget_states.lock().await.clone();
+        hidden_ids.retain(|id| states.contains_key(id));
+
+        for state in states.values().filter(|state| state.hides_on_fullscreen()) {
+          let Some(window) = app_handle.get_webview_window(&state.id) else {
+            continue;
+          };
+
+          let Ok(Some(monitor)) = window.current_monitor() else {
+            continue;
+          };
+
+          let is_covered = !foreground.is_desktop
+            && !foreground.is_maximized
+            && foreground.bounds.covers(
+              monitor.position().x,
+              monitor.position().y,
+              monitor.size().width as i32,
+              monitor.size().height as i32,
+            );
+
+          if is_covered && !hidden_ids.contains(&state.id) {
+            info!("Hiding widget {} for fullscreen window.", state.id);
+            let _ = window.hide();
+            hidden_ids.insert(state.id.clone());
+
+            if let Err(err) = Self::release_hidden_dock_space(&window, &docked_widgets).await {
+              warn!("Failed to release dock space of {}: {:?}", state.id, err);
+            }
+          } else if !is_covered && hidden_ids.remove(&state.id) && state.is_visible {
+            info!("Restoring widget {} after fullscreen window.", state.id);
+
+            if let Err(err) =
+              Self::reserve_shown_dock_space(&window, &docked_widgets, &auto_hide_states).await
+            {
+              warn!("Failed to reserve dock space of {}: {:?}", state.id, err);
+            }
+
+            let _ = window.show();
+          }
+        }
+      }
+    });
+  }
+
+  /// Listens for foreground window changes and hides widgets with
+  /// `hideOnFullscreen` while a fullscreen window covers their monitor.
+  ///
+  /// Not yet supported on this platform.
+  #[cfg(not(target_os = "windows"))]
+  fn listen_fullscreen(
+    _app_handle: AppHandle,
+    _widget_states: Arc<Mutex<HashMap<String, WidgetState>>>,
+    _auto_hide_states: Arc<Mutex<HashMap<String, AutoHideState>>>,
+    _docked_widgets: Arc<Mutex<HashMap<String, DockedWidget>>>,
+  ) {
+  }
+
   /// Opens widget from a given config path.
   ///
-  /// Config path must be absolute.
+  /// Config path must be absolute. Presets are opened at most once per
+  /// monitor, so this no-ops if the preset is already open.
   pub async fn start_widget(
     &self,
     config_path: &PathBuf,
     open_options: &WidgetOpenOptions,
+  ) -> anyhow::Result<()> {
+    let skip_open_monitors = matches!(open_options, WidgetOpenOptions::Preset(_));
+
+    self
+      .open_widget(config_path, open_options, skip_open_monitors)
+      .await
+  }
+
+  /// Opens a window for each monitor matched by the widget's placement.
+  ///
+  /// If `skip_open_monitors` is set, monitors that already have a window
+  /// with the same config path and open options are skipped.
+  async fn open_widget(
+    &self,
+    config_path: &PathBuf,
+    open_options: &WidgetOpenOptions,
+    skip_open_monitors: bool,
   ) -> anyhow::Result<()> {
     let (config_path, widget_config) = self
       .config
@@ -179,28 +567,23 @@ impl WidgetFactory {
       .await
       .with_context(|| format!("No config found at path '{}'.", config_path.display()))?;
 
-    // No-op if preset is already open.
-    if let WidgetOpenOptions::Preset(_) = open_options {
-      let is_preset_open = {
-        self
-          .widget_states
-          .lock()
-          .await
-          .values()
-          .find(|state| state.config_path == config_path && state.open_options == *open_options)
-          .is_some()
-      };
-
-      if is_preset_open {
-        return Ok(());
-      }
-    }
+    let open_monitors = match skip_open_monitors {
+      false => vec![],
+      true => self
+        .widget_states
+        .lock()
+        .await
+        .values()
+        .filter(|state| state.config_path == config_path && state.open_options == *open_options)
+        .map(|state| state.monitor.clone())
+        .collect::<Vec<_>>(),
+    };
 
     // Extract placement from widget preset (if applicable).
-    let placement = match open_options {
-      WidgetOpenOptions::Standalone(placement) => placement,
+    let (placement, preset_id) = match open_options {
+      WidgetOpenOptions::Standalone(placement) => (placement, None),
       WidgetOpenOptions::Preset(name) => {
-        &widget_config
+        let preset = widget_config
           .presets
           .iter()
           .find(|preset| preset.name == *name)
@@ -210,16 +593,61 @@ impl WidgetFactory {
               name,
               config_path.display()
             )
-          })?
-          .placement
+          })?;
+
+        (&preset.placement, preset.id.as_deref())
       }
     };
 
-    for coordinates in self.widget_coordinates(placement).await {
-      let new_count = self.widget_count.fetch_add(1, Ordering::Relaxed) + 1;
+    if let Some(id) = preset_id {
+      if id.is_empty() || sanitize_id(id) != id {
+        bail!(
+          "Invalid preset ID '{}' at config '{}'. Only letters, digits, '-' and '_' are allowed.",
+          id,
+          config_path.display()
+        );
+      }
+    }
+
+    let all_coordinates = self.widget_coordinates(placement).await;
+    let is_multi_monitor = all_coordinates.len() > 1;
+
+    for coordinates in all_coordinates {
+      let is_monitor_open = open_monitors
+        .iter()
+        .any(|monitor| monitor.is_same_device(&coordinates.monitor));
+
+      if is_monitor_open {
+        continue;
+      }
+
+      let instance = self.widget_count.fetch_add(1, Ordering::Relaxed) + 1;
+
+      let device_id = sanitize_id(&coordinates.monitor.device_id());
 
-      // Use running widget count as a unique label for the Tauri window.
-      let widget_id = format!("widget-{}", new_count);
+      let base_id = match preset_id {
+        Some(id) if is_multi_monitor => format!("widget-{}-{}", id, device_id),
+        Some(id) => format!("widget-{}", id),
+        None => derive_widget_id(
+          &self.config.formatted_widget_path(&config_path),
+          open_options,
+          &device_id,
+        ),
+      };
+
+      let widget_id = self.unique_widget_id(&base_id).await;
+
+      // User placements are persisted relative to the placement on the
+      // monitor, so it's kept for the window events.
+      let monitor_placement = coordinates.placement.clone();
+
+      let coordinates = self
+        .with_user_placement(&widget_id, &widget_config, coordinates)
+        .await;
+
+      // A previous window with the same ID might still be closing (e.g.
+      // when the widget is relaunched).
+      self.wait_for_window_close(&widget_id).await?;
 
       info!(
         "Creating window for {} from {}",
@@ -248,11 +676,15 @@ impl WidgetFactory {
         config_path: config_path.clone(),
         html_path: html_path.clone(),
         open_options: open_options.clone(),
+        monitor: coordinates.monitor.clone(),
+        is_visible: true,
+        instance,
       };
 
       // Widgets from the same top-level directory share their browser
       // cache (i.e. `localStorage`, `sessionStorage`, SW cache, etc.).
       let cache_id = BASE64_STANDARD.encode(parent_dir.to_unicode_string());
+      let data_dir = self.webview_data_dir(&cache_id).await?;
 
       let window = WebviewWindowBuilder::new(&self.app_handle, widget_id.clone(), webview_url)
         .title(format!(
@@ -267,71 +699,333 @@ impl WidgetFactory {
         .decorations(false)
         .resizable(widget_config.resizable)
         .initialization_script(&self.initialization_script(&state)?)
-        .data_directory(
-          // TODO: Add this as an ext method on the Tauri window.
-          self
-            .app_handle
-            .path()
-            .resolve(format!(".edgebar/tmp-{}", cache_id), BaseDirectory::Home)
-            .context("Unable to get home directory.")
-            .unwrap(),
-        )
+        .data_directory(data_dir)
         .build()?;
 
       // Widget coordinates might be modified when docked to an edge.
-      let (size, position) = match placement.dock_to_edge.enabled {
-        false => (coordinates.size, coordinates.position),
-        true => self.dock_to_edge(&window, &placement.dock_to_edge, &coordinates)?,
+      let docked_bounds = match coordinates.placement.dock_to_edge.enabled {
+        false => None,
+        true => {
+          self
+            .dock_window(&window, &widget_id, &coordinates, instance)
+            .await?
+        }
       };
 
-      info!("Positioning widget to {:?} {:?}", size, position);
-      let _ = window.set_size(size);
-      let _ = window.set_position(position);
+      let (size, position) = docked_bounds
+        .as_ref()
+        .map(|bounds| (bounds.size, bounds.position))
+        .unwrap_or((coordinates.size, coordinates.position));
 
-      // On Windows, we need to set the position twice to account for
-      // different monitor scale factors.
-      let _ = window.set_size(size);
-      let _ = window.set_position(position);
+      info!("Positioning widget to {:?} {:?}", size, position);
+      Self::set_window_bounds(&window, size, position);
 
       // On Windows, Tauri's `skip_taskbar` option isn't 100% reliable,
       // so we also set the window as a tool window.
+      #[cfg(target_os = "windows")]
       let _ = window
         .as_ref()
         .window()
         .set_tool_window(!widget_config.shown_in_taskbar);
 
-      state.window_handle = {
-        let handle = window.hwnd().context("Failed to get window handle.")?;
-
-        Some(handle.0 as isize)
-      };
+      state.window_handle = window_handle(&window).ok();
 
       {
         let mut widget_states = self.widget_states.lock().await;
         widget_states.insert(state.id.clone(), state.clone());
       }
 
-      self.register_window_events(&window, widget_id)?;
+      if let Some(bounds) = docked_bounds {
+        if coordinates.placement.dock_to_edge.auto_hide.enabled {
+          let auto_hide_state = AutoHideState::new(
+            bounds,
+            coordinates.monitor.clone(),
+            &coordinates.placement.dock_to_edge.auto_hide,
+            instance,
+          );
+
+          self.start_auto_hide(&widget_id, auto_hide_state).await;
+        }
+      }
+
+      self.register_window_events(&window, widget_id, instance, monitor_placement)?;
       self.open_tx.send(state)?;
     }
 
     Ok(())
   }
 
-  /// Dock the widget window to a given edge. This might result in the
-  /// window being resized or repositioned (e.g. if a window is already
-  /// docked to the given edge).
+  /// Sets the size and position of a widget window.
+  fn set_window_bounds(
+    window: &tauri::WebviewWindow,
+    size: PhysicalSize<i32>,
+    position: PhysicalPosition<i32>,
+  ) {
+    let _ = window.set_size(size);
+    let _ = window.set_position(position);
+
+    // On Windows, we need to set the position twice to account for
+    // different monitor scale factors.
+    let _ = window.set_size(size);
+    let _ = window.set_position(position);
+  }
+
+  /// Updates open widgets after monitors have changed.
   ///
-  /// Returns the new window size and position.
-  fn dock_to_edge(
+  /// Widgets whose monitor still exists are moved to their recomputed
+  /// placement, and widgets whose monitor has disappeared are closed.
+  /// Windows are then opened for newly matching monitors. Docked widgets
+  /// are relaunched instead of moved, since their app bar space needs to
+  /// be reallocated.
+  pub async fn reposition_all(&self) -> anyhow::Result<()> {
+    let states = { self.widget_states.lock().await.clone() };
+
+    let mut relaunch_ids = vec![];
+    let mut widgets_to_open = vec![];
+
+    for state in states.values() {
+      let Some(placement) = state.placement() else {
+        continue;
+      };
+
+      let widget_key = (state.config_path.clone(), state.open_options.clone());
+
+      if !widgets_to_open.contains(&widget_key) {
+        widgets_to_open.push(widget_key);
+      }
+
+      let coordinates = self
+        .widget_coordinates(placement)
+        .await
+        .into_iter()
+        .find(|coordinates| coordinates.monitor.is_same_device(&state.monitor));
+
+      let Some(coordinates) = coordinates else {
+        info!("Closing widget {} as its monitor was removed.", state.id);
+        let _ = self.stop_by_id(&state.id);
+        continue;
+      };
+
+      let coordinates = self
+        .with_user_placement(&state.id, &state.config, coordinates)
+        .await;
+
+      if coordinates.placement.dock_to_edge.enabled {
+        relaunch_ids.push(state.id.clone());
+        continue;
+      }
+
+      if let Some(window) = self.app_handle.get_webview_window(&state.id) {
+        info!(
+          "Moving widget {} to {:?} {:?}",
+          state.id, coordinates.size, coordinates.position
+        );
+
+        Self::set_window_bounds(&window, coordinates.size, coordinates.position);
+      }
+
+      if let Some(state) = self.widget_states.lock().await.get_mut(&state.id) {
+        state.monitor = coordinates.monitor;
+      }
+    }
+
+    self.relaunch_by_ids(&relaunch_ids).await?;
+
+    for (config_path, open_options) in widgets_to_open {
+      self.open_widget(&config_path, &open_options, true).await?;
+    }
+
+    Ok(())
+  }
+
+  /// Docks the widget window to an edge of its monitor.
+  ///
+  /// Widgets that are docked to the same monitor edge are stacked by
+  /// their `order`. Auto-hiding widgets keep their slot in the stack
+  /// while hidden.
+  ///
+  /// Returns the new window bounds, or `None` if the widget can't be
+  /// docked.
+  async fn dock_window(
     &self,
     window: &tauri::WebviewWindow,
+    widget_id: &str,
+    coordinates: &WidgetCoordinates,
+    instance: u32,
+  ) -> anyhow::Result<Option<DockedBounds>> {
+    let dock_config = &coordinates.placement.dock_to_edge;
+
+    let Some(reservation) = Self::dock_reservation(dock_config, coordinates) else {
+      return Ok(None);
+    };
+
+    // Reserving space relies on X11 window properties, which aren't
+    // available for windows on Wayland.
+    #[cfg(target_os = "linux")]
+    {
+      let window = window.as_ref().window();
+
+      if let Err(err) = window.x11_window_id() {
+        warn!("Placing widget {} undocked: {:?}", widget_id, err);
+        return Ok(None);
+      }
+
+      window.set_dock_window_type()?;
+    }
+
+    self.docked_widgets.lock().await.insert(
+      widget_id.to_string(),
+      DockedWidget {
+        coordinates: coordinates.clone(),
+        reservation,
+        instance,
+        offset: None,
+        bounds: None,
+      },
+    );
+
+    self
+      .layout_dock_edge(&coordinates.monitor, reservation.edge)
+      .await?;
+
+    Ok(
+      self
+        .docked_widgets
+        .lock()
+        .await
+        .get(widget_id)
+        .and_then(|docked_widget| docked_widget.bounds.clone()),
+    )
+  }
+
+  /// Re-docks stacked widgets on all monitor edges (e.g. after a docked
+  /// widget was closed).
+  pub async fn layout_docked_widgets(&self) -> anyhow::Result<()> {
+    let mut monitor_edges = vec![];
+
+    for docked_widget in self.docked_widgets.lock().await.values() {
+      let monitor_edge = (
+        docked_widget.coordinates.monitor.clone(),
+        docked_widget.reservation.edge,
+      );
+
+      if !monitor_edges.contains(&monitor_edge) {
+        monitor_edges.push(monitor_edge);
+      }
+    }
+
+    for (monitor, edge) in monitor_edges {
+      self.layout_dock_edge(&monitor, edge).await?;
+    }
+
+    Ok(())
+  }
+
+  /// Stacks the widgets that are docked to the given monitor edge.
+  ///
+  /// Only widgets whose offset from the edge has changed are re-docked.
+  async fn layout_dock_edge(&self, monitor: &Monitor, edge: DockEdge) -> anyhow::Result<()> {
+    let mut docked_widgets = self.docked_widgets.lock().await;
+
+    let entries = docked_widgets
+      .iter_mut()
+      .filter(|(_, docked_widget)| {
+        docked_widget.reservation.edge == edge
+          && docked_widget.coordinates.monitor.is_same_device(monitor)
+      })
+      .collect::<Vec<_>>();
+
+    let slots = entries
+      .iter()
+      .map(|(_, docked_widget)| DockSlot {
+        order: docked_widget
+          .coordinates
+          .placement
+          .dock_to_edge
+          .order
+          .unwrap_or(0),
+        sequence: docked_widget.instance,
+        length: docked_widget.reservation.reserved_length,
+      })
+      .collect::<Vec<_>>();
+
+    let offsets = stack_dock_slots(&slots);
+
+    // Deallocate the app bars of moved widgets first, such that they can
+    // be re-allocated in stacking order without overlapping.
+    let mut moved = vec![];
+
+    for ((widget_id, docked_widget), offset) in entries.into_iter().zip(offsets) {
+      if docked_widget.offset == Some(offset) {
+        continue;
+      }
+
+      let Some(window) = self.app_handle.get_webview_window(widget_id) else {
+        continue;
+      };
+
+      if docked_widget.offset.is_some() {
+        release_dock_space(window_handle(&window)?)?;
+      }
+
+      moved.push((widget_id, window, docked_widget, offset));
+    }
+
+    moved.sort_by_key(|(_, _, _, offset)| *offset);
+
+    let mut auto_hide_states = self.auto_hide_states.lock().await;
+
+    for (widget_id, window, docked_widget, offset) in moved {
+      let bounds = Self::dock_to_edge(
+        &window,
+        &docked_widget.reservation,
+        &docked_widget.coordinates,
+        offset,
+      )?;
+
+      Self::set_window_bounds(&window, bounds.size, bounds.position);
+
+      // Keep the bounds of auto-hiding widgets in sync, and move them
+      // back out of view if currently hidden.
+      if let Some(state) = auto_hide_states
+        .get_mut(widget_id)
+        .filter(|state| state.instance == docked_widget.instance)
+      {
+        let mut new_state = AutoHideState::new(
+          bounds.clone(),
+          state.monitor.clone(),
+          &docked_widget.coordinates.placement.dock_to_edge.auto_hide,
+          state.instance,
+        );
+
+        new_state.is_hidden = state.is_hidden;
+        new_state.is_pinned = state.is_pinned;
+
+        if new_state.is_hidden {
+          release_dock_space(window_handle(&window)?)?;
+          window.set_position(new_state.hidden_position)?;
+        }
+
+        *state = new_state;
+      }
+
+      docked_widget.offset = Some(offset);
+      docked_widget.bounds = Some(bounds);
+    }
+
+    Ok(())
+  }
+
+  /// Gets the space to reserve for a widget docked to an edge.
+  ///
+  /// Returns `None` if the widget can't be docked.
+  fn dock_reservation(
     dock_config: &DockConfig,
     coords: &WidgetCoordinates,
-  ) -> anyhow::Result<(PhysicalSize<i32>, PhysicalPosition<i32>)> {
+  ) -> Option<DockReservation> {
     // Disallow docking with a centered anchor point. Doesn't make sense.
     if coords.anchor == AnchorPoint::Center {
-      return Ok((coords.size, coords.position));
+      return None;
     }
 
     let edge = dock_config.edge.unwrap_or_else(|| coords.closest_edge());
@@ -355,7 +1049,12 @@ impl WidgetFactory {
     // not be smaller than the size of the window.
     let window_margin = dock_config
       .window_margin
-      .to_px_scaled(window_length as i32, coords.monitor.scale_factor)
+      .to_px_in_viewport(
+        window_length as i32,
+        coords.monitor.width as i32,
+        coords.monitor.height as i32,
+        coords.monitor.scale_factor,
+      )
       .clamp(-coords.size.height, i32::MAX);
 
     let monitor_length = if edge.is_horizontal() {
@@ -370,6 +1069,31 @@ impl WidgetFactory {
     let reserved_length =
       (offset + window_length + window_margin).clamp(0, monitor_length as i32 / 2);
 
+    Some(DockReservation {
+      edge,
+      window_margin,
+      reserved_length,
+    })
+  }
+
+  /// Dock the widget window to the edge of the given reservation. This
+  /// might result in the window being resized or repositioned (e.g. if a
+  /// window is already docked to the given edge).
+  ///
+  /// The reserved space starts `stack_offset` away from the monitor edge
+  /// (e.g. after other widgets docked to the same edge).
+  fn dock_to_edge(
+    window: &tauri::WebviewWindow,
+    reservation: &DockReservation,
+    coords: &WidgetCoordinates,
+    stack_offset: i32,
+  ) -> anyhow::Result<DockedBounds> {
+    let DockReservation {
+      edge,
+      window_margin,
+      reserved_length,
+    } = *reservation;
+
     let reserve_size = if edge.is_horizontal() {
       PhysicalSize::new(coords.monitor.width as i32, reserved_length)
     } else {
@@ -377,22 +1101,20 @@ impl WidgetFactory {
     };
 
     let reserve_position = match edge {
-      DockEdge::Top | DockEdge::Left => PhysicalPosition::new(coords.monitor.x, coords.monitor.y),
+      DockEdge::Top => PhysicalPosition::new(coords.monitor.x, coords.monitor.y + stack_offset),
+      DockEdge::Left => PhysicalPosition::new(coords.monitor.x + stack_offset, coords.monitor.y),
       DockEdge::Bottom => PhysicalPosition::new(
         coords.monitor.x,
-        coords.monitor.y + coords.monitor.height as i32 - reserved_length,
+        coords.monitor.y + coords.monitor.height as i32 - reserved_length - stack_offset,
       ),
       DockEdge::Right => PhysicalPosition::new(
-        coords.monitor.x + coords.monitor.width as i32 - reserved_length,
+        coords.monitor.x + coords.monitor.width as i32 - reserved_length - stack_offset,
         coords.monitor.y,
       ),
     };
 
     let (allocated_size, allocated_position) =
-      window
-        .as_ref()
-        .window()
-        .allocate_app_bar(reserve_size, reserve_position, edge)?;
+      reserve_dock_space(window, reserve_size, reserve_position, edge)?;
 
     // Adjust the size to account for the window margin.
     let final_size = if edge.is_horizontal() {
@@ -428,27 +1150,356 @@ impl WidgetFactory {
       final_position
     );
 
-    Ok((final_size, final_position))
+    Ok(DockedBounds {
+      edge,
+      size: final_size,
+      position: final_position,
+      reserve_size,
+      reserve_position,
+    })
   }
 
-  /// Opens presets that are configured to be launched on startup.
-  pub async fn startup(&self) -> anyhow::Result<()> {
-    let startup_configs = self.config.startup_configs().await;
-
-    for startup_config in startup_configs {
-      self
-        .start_widget(
-          &startup_config.path,
-          &WidgetOpenOptions::Preset(startup_config.preset),
-        )
-        .await?;
+  /// Watches the cursor position for a docked widget that auto-hides.
+  ///
+  /// The widget is hidden once the cursor has left it for the configured
+  /// delay, and revealed when the cursor touches the monitor edge. The
+  /// watcher stops once the window is closed or replaced.
+  async fn start_auto_hide(&self, widget_id: &str, auto_hide_state: AutoHideState) {
+    let instance = auto_hide_state.instance;
+
+    {
+      let mut auto_hide_states = self.auto_hide_states.lock().await;
+      auto_hide_states.insert(widget_id.to_string(), auto_hide_state);
     }
 
-    Ok(())
-  }
+    let app_handle = self.app_handle.clone();
+    let auto_hide_states = self.auto_hide_states.clone();
+    let widget_id = widget_id.to_string();
 
-  fn initialization_script(&self, state: &WidgetState) -> anyhow::Result<String> {
-    let state_script = format!("window.__EDGEBAR_STATE={};", serde_json::to_string(state)?);
+    task::spawn(async move {
+      let mut interval = tokio::time::interval(AUTO_HIDE_POLL_INTERVAL);
+      interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+      let mut last_hovered = Instant::now();
+
+      loop {
+        interval.tick().await;
+
+        let window = app_handle.get_webview_window(&widget_id);
+
+        let Some(window) = window else {
+          let mut auto_hide_states = auto_hide_states.lock().await;
+
+          if auto_hide_states
+            .get(&widget_id)
+            .is_some_and(|state| state.instance == instance)
+          {
+            auto_hide_states.remove(&widget_id);
+          }
+
+          break;
+        };
+
+        // Skip widgets that are hidden (e.g. explicitly or for a
+        // fullscreen window), such that they aren't slid into view.
+        if !window.is_visible().unwrap_or(true) {
+          last_hovered = Instant::now();
+          continue;
+        }
+
+        let Ok(cursor) = app_handle.cursor_position() else {
+          continue;
+        };
+
+        let cursor = PhysicalPosition::new(cursor.x as i32, cursor.y as i32);
+
+        // Decide on a transition while holding the lock, but animate
+        // after releasing it.
+        let transition = {
+          let mut auto_hide_states = auto_hide_states.lock().await;
+
+          let Some(state) = auto_hide_states
+            .get_mut(&widget_id)
+            .filter(|state| state.instance == instance)
+          else {
+            break;
+          };
+
+          if state.is_hidden {
+            let should_reveal = state.is_at_edge(cursor);
+
+            if should_reveal {
+              state.is_hidden = false;
+              last_hovered = Instant::now();
+            }
+
+            should_reveal.then(|| (state.clone(), false))
+          } else if state.is_pinned || state.is_hovered(cursor) {
+            last_hovered = Instant::now();
+            None
+          } else {
+            let should_hide = last_hovered.elapsed() >= state.hide_delay;
+
+            if should_hide {
+              state.is_hidden = true;
+            }
+
+            should_hide.then(|| (state.clone(), true))
+          }
+        };
+
+        if let Some((state, is_hidden)) = transition {
+          if let Err(err) = Self::slide_auto_hide(&window, &state, is_hidden).await {
+            warn!("Failed to auto-hide widget {}: {:?}", widget_id, err);
+          }
+        }
+      }
+    });
+  }
+
+  /// Slides an auto-hiding widget in or out of view.
+  ///
+  /// App bar space is released while the widget is hidden, and reserved
+  /// again before it is revealed.
+  async fn slide_auto_hide(
+    window: &tauri::WebviewWindow,
+    state: &AutoHideState,
+    is_hidden: bool,
+  ) -> anyhow::Result<()> {
+    let (from, to) = match is_hidden {
+      true => (state.bounds.position, state.hidden_position),
+      false => (state.hidden_position, state.bounds.position),
+    };
+
+    if !is_hidden {
+      reserve_dock_space(
+        window,
+        state.bounds.reserve_size,
+        state.bounds.reserve_position,
+        state.bounds.edge,
+      )?;
+    }
+
+    for frame in 1..=AUTO_HIDE_ANIMATION_FRAMES {
+      let position = PhysicalPosition::new(
+        from.x + (to.x - from.x) * frame / AUTO_HIDE_ANIMATION_FRAMES,
+        from.y + (to.y - from.y) * frame / AUTO_HIDE_ANIMATION_FRAMES,
+      );
+
+      window.set_position(position)?;
+      tokio::time::sleep(Duration::from_millis(15)).await;
+    }
+
+    if is_hidden {
+      release_dock_space(window_handle(window)?)?;
+    }
+
+    Ok(())
+  }
+
+  /// Releases the reserved space of a docked widget that is hidden.
+  async fn release_hidden_dock_space(
+    window: &tauri::WebviewWindow,
+    docked_widgets: &Mutex<HashMap<String, DockedWidget>>,
+  ) -> anyhow::Result<()> {
+    if docked_widgets.lock().await.contains_key(window.label()) {
+      release_dock_space(window_handle(window)?)?;
+    }
+
+    Ok(())
+  }
+
+  /// Reserves the space of a docked widget again before it is shown.
+  ///
+  /// Auto-hiding widgets only reserve space while revealed.
+  async fn reserve_shown_dock_space(
+    window: &tauri::WebviewWindow,
+    docked_widgets: &Mutex<HashMap<String, DockedWidget>>,
+    auto_hide_states: &Mutex<HashMap<String, AutoHideState>>,
+  ) -> anyhow::Result<()> {
+    let bounds = docked_widgets
+      .lock()
+      .await
+      .get(window.label())
+      .and_then(|docked_widget| docked_widget.bounds.clone());
+
+    let is_auto_hidden = auto_hide_states
+      .lock()
+      .await
+      .get(window.label())
+      .is_some_and(|auto_hide_state| auto_hide_state.is_hidden);
+
+    if let Some(bounds) = bounds.filter(|_| !is_auto_hidden) {
+      reserve_dock_space(
+        window,
+        bounds.reserve_size,
+        bounds.reserve_position,
+        bounds.edge,
+      )?;
+    }
+
+    Ok(())
+  }
+
+  /// Opens presets that are configured to be launched on startup.
+  ///
+  /// If `restoreSession` is enabled and a previous session exists, its
+  /// widgets are opened instead. Sessions are only restored when no
+  /// widgets are open yet, to avoid duplicating standalone widgets.
+  pub async fn startup(&self) -> anyhow::Result<()> {
+    let should_restore = self.config.runtime_settings().await.restore_session
+      && self.widget_states.lock().await.is_empty();
+
+    if should_restore {
+      if let Some(session) = Session::read(&self.config.session_path())? {
+        return self.restore_session(session).await;
+      }
+    }
+
+    let startup_configs = self.config.startup_configs().await;
+
+    for startup_config in startup_configs {
+      self
+        .start_widget(
+          &startup_config.path,
+          &WidgetOpenOptions::Preset(startup_config.preset),
+        )
+        .await?;
+    }
+
+    Ok(())
+  }
+
+  /// Opens the widgets of a previous session.
+  async fn restore_session(&self, session: Session) -> anyhow::Result<()> {
+    info!(
+      "Restoring {} widget(s) from session.",
+      session.widgets.len()
+    );
+
+    for widget in session.widgets {
+      // Configs might have been removed since the session was saved.
+      if let Err(err) = self
+        .start_widget(&widget.config_path, &widget.open_options)
+        .await
+      {
+        warn!("Failed to restore widget from session: {:?}", err);
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Persists the currently open widgets to the session file, if
+  /// `restoreSession` is enabled.
+  pub async fn save_session(&self) -> anyhow::Result<()> {
+    if self.is_exiting.load(Ordering::Relaxed)
+      || !self.config.runtime_settings().await.restore_session
+    {
+      return Ok(());
+    }
+
+    let mut states = self.states().await.into_values().collect::<Vec<_>>();
+    states.sort_by(|a, b| a.id.cmp(&b.id));
+
+    let mut session = Session::default();
+
+    for state in states {
+      let widget = SessionWidget {
+        config_path: self.config.to_relative_path(&state.config_path),
+        open_options: state.open_options,
+      };
+
+      // Presets can span multiple windows (e.g. one per monitor).
+      if !session.widgets.contains(&widget) {
+        session.widgets.push(widget);
+      }
+    }
+
+    session.write(&self.config.session_path())
+  }
+
+  /// Marks the app as exiting, such that widgets closed from here on
+  /// are kept in the persisted session.
+  pub fn set_exiting(&self) {
+    self.is_exiting.store(true, Ordering::Relaxed);
+  }
+
+  /// Switches to the profile with the given name.
+  ///
+  /// Closes widgets that are not part of the profile's startup configs,
+  /// relaunches widgets whose config changed due to the profile's
+  /// variable overrides, and opens any missing widgets. The changed
+  /// configs are broadcast once the switch is done.
+  pub async fn switch_profile(&self, name: &str) -> anyhow::Result<()> {
+    let changed_configs = self.config.set_active_profile(name).await?;
+
+    let target_widgets = self
+      .config
+      .startup_configs()
+      .await
+      .into_iter()
+      .filter_map(|startup_config| {
+        let config_path = self.config.to_absolute_path(&startup_config.path).ok()?;
+        Some((
+          config_path,
+          WidgetOpenOptions::Preset(startup_config.preset),
+        ))
+      })
+      .collect::<Vec<_>>();
+
+    let mut relaunch_ids = vec![];
+
+    for state in self.states().await.into_values() {
+      let is_target = target_widgets
+        .iter()
+        .any(|(path, options)| *path == state.config_path && *options == state.open_options);
+
+      if !is_target {
+        info!("Closing widget {} for profile '{}'.", state.id, name);
+        self.stop_by_id(&state.id)?;
+      } else if changed_configs.contains_key(&state.config_path) {
+        relaunch_ids.push(state.id);
+      }
+    }
+
+    self.relaunch_by_ids(&relaunch_ids).await?;
+
+    // Widgets that are already open are skipped by `start_widget`.
+    for (config_path, open_options) in &target_widgets {
+      if let Err(err) = self.start_widget(config_path, open_options).await {
+        error!("Failed to open widget for profile '{}': {:?}", name, err);
+      }
+    }
+
+    if !changed_configs.is_empty() {
+      self.config.widget_configs_change_tx.send(changed_configs)?;
+    }
+
+    Ok(())
+  }
+
+  /// Returns the directory to store webview data in for a given cache ID.
+  ///
+  /// Uses the `webviewDataDir` setting if set, otherwise `~/.edgebar`.
+  async fn webview_data_dir(&self, cache_id: &str) -> anyhow::Result<PathBuf> {
+    let data_dir = match self.config.runtime_settings().await.webview_data_dir {
+      Some(dir) => self.config.config_dir.join(dir),
+      None => self
+        .app_handle
+        .path()
+        .resolve(".edgebar", BaseDirectory::Home)
+        .context("Unable to get home directory.")?,
+    };
+
+    Ok(data_dir.join(format!("tmp-{}", cache_id)))
+  }
+
+  fn initialization_script(&self, state: &WidgetState) -> anyhow::Result<String> {
+    let state_script = format!(
+      "window.__EDGEBAR_STATE={};\nwindow.__EDGEBAR_ASSET_SERVER_PORT={};",
+      serde_json::to_string(state)?,
+      asset_server_port()
+    );
 
     let sw_script = include_str!("../resources/initialization-script.js");
 
@@ -456,31 +1507,103 @@ impl WidgetFactory {
   }
 
   /// Registers window events for a given widget.
+  ///
+  /// The given placement is the widget's placement on its monitor
+  /// without any user placement applied, which moves and resizes by the
+  /// user are persisted relative to.
   fn register_window_events(
     &self,
     window: &tauri::WebviewWindow,
     widget_id: String,
+    instance: u32,
+    placement: WidgetPlacement,
   ) -> anyhow::Result<()> {
     let widget_states = self.widget_states.clone();
     let close_tx = self.close_tx.clone();
+    let docked_widgets = self.docked_widgets.clone();
+    let user_placements = self.user_placements.clone();
+    let user_placements_path = self.config.user_placements_path();
+    let move_count = Arc::new(AtomicU32::new(0));
+    let app_handle = self.app_handle.clone();
 
     window.on_window_event(move |event| {
+      if let WindowEvent::Moved(_) | WindowEvent::Resized(_) = event {
+        if placement.dock_to_edge.enabled {
+          return;
+        }
+
+        let app_handle = app_handle.clone();
+        let widget_states = widget_states.clone();
+        let user_placements = user_placements.clone();
+        let user_placements_path = user_placements_path.clone();
+        let widget_id = widget_id.clone();
+        let placement = placement.clone();
+        let move_count = move_count.clone();
+        let count = move_count.fetch_add(1, Ordering::Relaxed) + 1;
+
+        task::spawn(async move {
+          tokio::time::sleep(USER_PLACEMENT_SAVE_DELAY).await;
+
+          // Only persist once the window has stopped moving.
+          if move_count.load(Ordering::Relaxed) != count {
+            return;
+          }
+
+          let Some(window) = app_handle.get_webview_window(&widget_id) else {
+            return;
+          };
+
+          let state = widget_states.lock().await.get(&widget_id).cloned();
+
+          let Some(state) = state.filter(|state| {
+            state.instance == instance && state.is_visible && state.config.persist_placement
+          }) else {
+            return;
+          };
+
+          if let Err(err) = Self::save_user_placement(
+            &window,
+            &state,
+            placement,
+            &user_placements,
+            &user_placements_path,
+          )
+          .await
+          {
+            warn!("Failed to save placement of {}: {:?}", widget_id, err);
+          }
+        });
+      }
+
       if let WindowEvent::Destroyed = event {
         let widget_states = widget_states.clone();
+        let docked_widgets = docked_widgets.clone();
         let close_tx = close_tx.clone();
         let widget_id = widget_id.clone();
 
         task::spawn(async move {
           let mut widget_states = widget_states.lock().await;
 
+          // Skip if the ID has since been reused by a new window (e.g.
+          // when the widget is relaunched).
+          let is_replaced = widget_states
+            .get(&widget_id)
+            .is_some_and(|state| state.instance != instance);
+
+          if is_replaced {
+            return;
+          }
+
           // Remove the widget state.
           let state = widget_states.remove(&widget_id);
 
           // Ensure appbar space is deallocated on close.
           if let Some(window_handle) = state.and_then(|state| state.window_handle) {
-            let _ = remove_app_bar(window_handle);
+            let _ = release_dock_space(window_handle);
           }
 
+          docked_widgets.lock().await.remove(&widget_id);
+
           // Broadcast the close event.
           if let Err(err) = close_tx.send(widget_id) {
             error!("Failed to send window close event: {:?}", err);
@@ -492,82 +1615,463 @@ impl WidgetFactory {
     Ok(())
   }
 
+  /// Persists the current bounds of a widget window as its user
+  /// placement, if they differ from its current placement.
+  async fn save_user_placement(
+    window: &tauri::WebviewWindow,
+    state: &WidgetState,
+    placement: WidgetPlacement,
+    user_placements: &Mutex<UserPlacements>,
+    user_placements_path: &PathBuf,
+  ) -> anyhow::Result<()> {
+    if window.is_minimized()? {
+      return Ok(());
+    }
+
+    let position = window.outer_position()?;
+    let size = window.outer_size()?;
+    let size = PhysicalSize::new(size.width as i32, size.height as i32);
+
+    let mut user_placements = user_placements.lock().await;
+
+    let Some(user_placement) = Self::user_placement_for_bounds(
+      placement,
+      user_placements.widgets.get(&state.id),
+      &state.monitor,
+      position,
+      size,
+      state.config.resizable,
+    ) else {
+      return Ok(());
+    };
+
+    info!("Saving user placement of {}.", state.id);
+
+    user_placements
+      .widgets
+      .insert(state.id.clone(), user_placement);
+
+    user_placements.write(user_placements_path)
+  }
+
+  /// Gets the user placement for the given window bounds.
+  ///
+  /// The given placement is the widget's placement on its monitor
+  /// without any user placement applied. Returns `None` if the window is
+  /// where it would be placed anyway (e.g. after being positioned on
+  /// open).
+  fn user_placement_for_bounds(
+    mut placement: WidgetPlacement,
+    user_placement: Option<&UserPlacement>,
+    monitor: &Monitor,
+    position: PhysicalPosition<i32>,
+    size: PhysicalSize<i32>,
+    is_resizable: bool,
+  ) -> Option<UserPlacement> {
+    let base_size = Self::monitor_coordinates(placement.clone(), monitor.clone()).size;
+
+    if let Some(user_placement) = user_placement {
+      user_placement.apply(&mut placement);
+    }
+
+    let coordinates = Self::monitor_coordinates(placement.clone(), monitor.clone());
+
+    if coordinates.position == position && coordinates.size == size {
+      return None;
+    }
+
+    // Offsets are stored in physical pixels relative to the anchor-point,
+    // such that the window is restored to the exact same bounds.
+    let anchor = Self::anchor_position(placement.anchor, monitor, size);
+    let is_resized = is_resizable && base_size != size;
+
+    Some(UserPlacement {
+      offset_x: LengthValue::from_ppx((position.x - anchor.x) as f32),
+      offset_y: LengthValue::from_ppx((position.y - anchor.y) as f32),
+      width: is_resized.then(|| LengthValue::from_ppx(size.width as f32)),
+      height: is_resized.then(|| LengthValue::from_ppx(size.height as f32)),
+    })
+  }
+
+  /// Applies the user placement of a widget (if any) to its coordinates.
+  async fn with_user_placement(
+    &self,
+    widget_id: &str,
+    widget_config: &WidgetConfig,
+    coordinates: WidgetCoordinates,
+  ) -> WidgetCoordinates {
+    if !widget_config.persist_placement || coordinates.placement.dock_to_edge.enabled {
+      return coordinates;
+    }
+
+    match self.user_placements.lock().await.widgets.get(widget_id) {
+      Some(user_placement) => {
+        let mut placement = coordinates.placement;
+        user_placement.apply(&mut placement);
+        Self::monitor_coordinates(placement, coordinates.monitor)
+      }
+      None => coordinates,
+    }
+  }
+
   /// Returns coordinates for window placement based on the given config.
-  async fn widget_coordinates(&self, placement: &WidgetPlacement) -> Vec<WidgetCoordinates> {
+  ///
+  /// Overrides of the placement are merged in for each monitor they
+  /// match.
+  async fn widget_coordinates(&self, base_placement: &WidgetPlacement) -> Vec<WidgetCoordinates> {
     let mut coordinates = vec![];
 
     let monitors = self
       .monitor_state
-      .monitors_by_selection(&placement.monitor_selection)
+      .monitors_by_selection(&base_placement.monitor_selection)
       .await;
 
+    let mut overrides = vec![];
+
+    for placement_override in &base_placement.overrides {
+      let override_monitors = self
+        .monitor_state
+        .monitors_by_selection(&placement_override.monitor_selection)
+        .await;
+
+      overrides.push((placement_override, override_monitors));
+    }
+
     for monitor in monitors {
-      let monitor_width = monitor.width as i32;
-      let monitor_height = monitor.height as i32;
-
-      // Pixel values should be scaled by the monitor's scale factor,
-      // whereas percentage values are left as-is. This is because the
-      // percentage values are already relative to the monitor's size.
-      let window_width = placement
-        .width
-        .to_px_scaled(monitor_width, monitor.scale_factor);
-
-      let window_height = placement
-        .height
-        .to_px_scaled(monitor_height, monitor.scale_factor);
-
-      let window_size = PhysicalSize::new(window_width, window_height);
-
-      let (anchor_x, anchor_y) = match placement.anchor {
-        AnchorPoint::TopLeft => (monitor.x, monitor.y),
-        AnchorPoint::TopCenter => (
-          monitor.x + (monitor_width / 2) - (window_size.width / 2),
-          monitor.y,
-        ),
-        AnchorPoint::TopRight => (monitor.x + monitor_width - window_size.width, monitor.y),
-        AnchorPoint::CenterLeft => (
-          monitor.x,
-          monitor.y + (monitor_height / 2) - (window_size.height / 2),
-        ),
-        AnchorPoint::Center => (
-          monitor.x + (monitor_width / 2) - (window_size.width / 2),
-          monitor.y + (monitor_height / 2) - (window_size.height / 2),
-        ),
-        AnchorPoint::CenterRight => (
-          monitor.x + monitor_width - window_size.width,
-          monitor.y + (monitor_height / 2) - (window_size.height / 2),
-        ),
-        AnchorPoint::BottomLeft => (monitor.x, monitor.y + monitor_height - window_size.height),
-        AnchorPoint::BottomCenter => (
-          monitor.x + (monitor_width / 2) - (window_size.width / 2),
-          monitor.y + monitor_height - window_size.height,
-        ),
-        AnchorPoint::BottomRight => (
-          monitor.x + monitor_width - window_size.width,
-          monitor.y + monitor_height - window_size.height,
-        ),
+      let mut placement = base_placement.clone();
+
+      for (placement_override, override_monitors) in &overrides {
+        if override_monitors.contains(&monitor) {
+          placement_override.apply(&mut placement);
+        }
+      }
+
+      coordinates.push(Self::monitor_coordinates(placement, monitor));
+    }
+
+    coordinates
+  }
+
+  /// Returns coordinates for window placement on the given monitor.
+  fn monitor_coordinates(placement: WidgetPlacement, monitor: Monitor) -> WidgetCoordinates {
+    let monitor_width = monitor.width as i32;
+    let monitor_height = monitor.height as i32;
+
+    // Pixel values should be scaled by the monitor's scale factor,
+    // whereas percentage values are left as-is. This is because the
+    // percentage values are already relative to the monitor's size.
+    // Physical pixels (`ppx`) are never scaled.
+    let window_width = placement.width.to_px_in_viewport(
+      monitor_width,
+      monitor_width,
+      monitor_height,
+      monitor.scale_factor,
+    );
+
+    let window_height = placement.height.to_px_in_viewport(
+      monitor_height,
+      monitor_width,
+      monitor_height,
+      monitor.scale_factor,
+    );
+
+    let window_size = PhysicalSize::new(window_width, window_height);
+
+    let anchor = Self::anchor_position(placement.anchor, &monitor, window_size);
+
+    let offset_x = placement.offset_x.to_px_in_viewport(
+      monitor_width,
+      monitor_width,
+      monitor_height,
+      monitor.scale_factor,
+    );
+
+    let offset_y = placement.offset_y.to_px_in_viewport(
+      monitor_height,
+      monitor_width,
+      monitor_height,
+      monitor.scale_factor,
+    );
+
+    let window_position = PhysicalPosition::new(anchor.x + offset_x, anchor.y + offset_y);
+
+    WidgetCoordinates {
+      size: window_size,
+      position: window_position,
+      offset: PhysicalPosition::new(offset_x, offset_y),
+      anchor: placement.anchor,
+      monitor,
+      placement,
+    }
+  }
+
+  /// Returns the position of the anchor-point for a window of the given
+  /// size on the given monitor.
+  fn anchor_position(
+    anchor: AnchorPoint,
+    monitor: &Monitor,
+    size: PhysicalSize<i32>,
+  ) -> PhysicalPosition<i32> {
+    let monitor_width = monitor.width as i32;
+    let monitor_height = monitor.height as i32;
+
+    let (x, y) = match anchor {
+      AnchorPoint::TopLeft => (monitor.x, monitor.y),
+      AnchorPoint::TopCenter => (
+        monitor.x + (monitor_width / 2) - (size.width / 2),
+        monitor.y,
+      ),
+      AnchorPoint::TopRight => (monitor.x + monitor_width - size.width, monitor.y),
+      AnchorPoint::CenterLeft => (
+        monitor.x,
+        monitor.y + (monitor_height / 2) - (size.height / 2),
+      ),
+      AnchorPoint::Center => (
+        monitor.x + (monitor_width / 2) - (size.width / 2),
+        monitor.y + (monitor_height / 2) - (size.height / 2),
+      ),
+      AnchorPoint::CenterRight => (
+        monitor.x + monitor_width - size.width,
+        monitor.y + (monitor_height / 2) - (size.height / 2),
+      ),
+      AnchorPoint::BottomLeft => (monitor.x, monitor.y + monitor_height - size.height),
+      AnchorPoint::BottomCenter => (
+        monitor.x + (monitor_width / 2) - (size.width / 2),
+        monitor.y + monitor_height - size.height,
+      ),
+      AnchorPoint::BottomRight => (
+        monitor.x + monitor_width - size.width,
+        monitor.y + monitor_height - size.height,
+      ),
+    };
+
+    PhysicalPosition::new(x, y)
+  }
+
+  /// Returns the given widget ID, or the ID with a numeric suffix if it
+  /// is already in use (e.g. a standalone widget opened twice).
+  async fn unique_widget_id(&self, widget_id: &str) -> String {
+    let widget_states = self.widget_states.lock().await;
+
+    (1..)
+      .map(|index| match index {
+        1 => widget_id.to_string(),
+        _ => format!("{}-{}", widget_id, index),
+      })
+      .find(|id| !widget_states.contains_key(id))
+      .unwrap_or_else(|| widget_id.to_string())
+  }
+
+  /// Waits for the Tauri window with the given label to be destroyed.
+  async fn wait_for_window_close(&self, label: &str) -> anyhow::Result<()> {
+    for _ in 0..50 {
+      if self.app_handle.get_webview_window(label).is_none() {
+        return Ok(());
+      }
+
+      tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+
+    bail!("Window '{}' is still open.", label)
+  }
+
+  /// Shows, hides or toggles a single widget by a given widget ID.
+  ///
+  /// The window is kept alive while hidden, such that showing it again
+  /// does not reload the webview.
+  pub async fn set_visibility_by_id(
+    &self,
+    widget_id: &str,
+    action: VisibilityAction,
+  ) -> anyhow::Result<()> {
+    let is_visible = self
+      .state_by_id(widget_id)
+      .await
+      .with_context(|| format!("No widget found with ID '{}'.", widget_id))?
+      .is_visible;
+
+    let should_show = match action {
+      VisibilityAction::Show => true,
+      VisibilityAction::Hide => false,
+      VisibilityAction::Toggle => !is_visible,
+    };
+
+    self.set_visible(widget_id, should_show).await
+  }
+
+  /// Shows, hides or toggles all widgets of the given preset name.
+  ///
+  /// When toggling, all widgets of the preset are hidden if any of them
+  /// is visible.
+  pub async fn set_visibility_by_preset(
+    &self,
+    config_path: &PathBuf,
+    preset_name: &str,
+    action: VisibilityAction,
+  ) -> anyhow::Result<()> {
+    let preset_states = self.open_preset_states(config_path, preset_name).await?;
+
+    let should_show = match action {
+      VisibilityAction::Show => true,
+      VisibilityAction::Hide => false,
+      VisibilityAction::Toggle => !preset_states.iter().any(|state| state.is_visible),
+    };
+
+    for state in preset_states {
+      self.set_visible(&state.id, should_show).await?;
+    }
+
+    Ok(())
+  }
+
+  /// Reveals, hides or toggles a single auto-hiding widget by a given
+  /// widget ID.
+  ///
+  /// A widget revealed this way stays revealed until it is hidden again
+  /// via this method.
+  pub async fn reveal_by_id(
+    &self,
+    widget_id: &str,
+    action: VisibilityAction,
+  ) -> anyhow::Result<()> {
+    let window = self
+      .app_handle
+      .get_webview_window(widget_id)
+      .context("No Tauri window found for the given widget ID.")?;
+
+    let state = {
+      let mut auto_hide_states = self.auto_hide_states.lock().await;
+
+      let state = auto_hide_states
+        .get_mut(widget_id)
+        .with_context(|| format!("Widget '{}' is not set to auto-hide.", widget_id))?;
+
+      let is_hidden = match action {
+        VisibilityAction::Show => false,
+        VisibilityAction::Hide => true,
+        VisibilityAction::Toggle => !state.is_hidden,
       };
 
-      let offset_x = placement
-        .offset_x
-        .to_px_scaled(monitor_width, monitor.scale_factor);
+      state.is_pinned = !is_hidden;
 
-      let offset_y = placement
-        .offset_y
-        .to_px_scaled(monitor_height, monitor.scale_factor);
+      if state.is_hidden == is_hidden {
+        return Ok(());
+      }
+
+      state.is_hidden = is_hidden;
+      state.clone()
+    };
 
-      let window_position = PhysicalPosition::new(anchor_x + offset_x, anchor_y + offset_y);
+    Self::slide_auto_hide(&window, &state, state.is_hidden).await
+  }
 
-      coordinates.push(WidgetCoordinates {
-        size: window_size,
-        position: window_position,
-        offset: PhysicalPosition::new(offset_x, offset_y),
-        monitor: monitor.clone(),
-        anchor: placement.anchor,
-      });
+  /// Reveals, hides or toggles all auto-hiding widgets of the given
+  /// preset name.
+  ///
+  /// When toggling, all widgets of the preset are hidden if any of them
+  /// is revealed.
+  pub async fn reveal_by_preset(
+    &self,
+    config_path: &PathBuf,
+    preset_name: &str,
+    action: VisibilityAction,
+  ) -> anyhow::Result<()> {
+    let preset_states = self.open_preset_states(config_path, preset_name).await?;
+
+    let action = match action {
+      VisibilityAction::Toggle => {
+        let auto_hide_states = self.auto_hide_states.lock().await;
+
+        let is_any_revealed = preset_states.iter().any(|state| {
+          auto_hide_states
+            .get(&state.id)
+            .is_some_and(|auto_hide_state| !auto_hide_state.is_hidden)
+        });
+
+        match is_any_revealed {
+          true => VisibilityAction::Hide,
+          false => VisibilityAction::Show,
+        }
+      }
+      action => action,
+    };
+
+    for state in preset_states {
+      self.reveal_by_id(&state.id, action).await?;
     }
 
-    coordinates
+    Ok(())
+  }
+
+  /// Returns states of the open widgets of the given preset name.
+  ///
+  /// Errors if no widgets of the preset are open.
+  async fn open_preset_states(
+    &self,
+    config_path: &PathBuf,
+    preset_name: &str,
+  ) -> anyhow::Result<Vec<WidgetState>> {
+    let config_path = self.config.to_absolute_path(config_path)?;
+
+    let preset_states = self
+      .states_by_path()
+      .await
+      .remove(&config_path)
+      .unwrap_or_default()
+      .into_iter()
+      .filter(|state| {
+        matches!(
+          &state.open_options,
+          WidgetOpenOptions::Preset(name) if name == preset_name
+        )
+      })
+      .collect::<Vec<_>>();
+
+    if preset_states.is_empty() {
+      bail!(
+        "No open widgets for preset '{}' at config '{}'.",
+        preset_name,
+        config_path.display()
+      );
+    }
+
+    Ok(preset_states)
+  }
+
+  /// Shows or hides the window of a widget and updates its state.
+  async fn set_visible(&self, widget_id: &str, is_visible: bool) -> anyhow::Result<()> {
+    let window = self
+      .app_handle
+      .get_webview_window(widget_id)
+      .context("No Tauri window found for the given widget ID.")?;
+
+    // Hidden widgets don't keep their docked space reserved.
+    match is_visible {
+      true => {
+        Self::reserve_shown_dock_space(&window, &self.docked_widgets, &self.auto_hide_states)
+          .await?;
+        window.show()?;
+      }
+      false => {
+        window.hide()?;
+        Self::release_hidden_dock_space(&window, &self.docked_widgets).await?;
+      }
+    }
+
+    let state = {
+      let mut widget_states = self.widget_states.lock().await;
+
+      widget_states.get_mut(widget_id).map(|state| {
+        state.is_visible = is_visible;
+        state.clone()
+      })
+    };
+
+    if let Some(state) = state {
+      self.visibility_change_tx.send(state)?;
+    }
+
+    Ok(())
   }
 
   /// Closes a single widget by a given widget ID.
@@ -643,7 +2147,7 @@ impl WidgetFactory {
         .inspect(|widget_state| {
           // Need to clean up any appbars prior to restarting.
           if let Some(window_handle) = widget_state.window_handle {
-            let _ = remove_app_bar(window_handle);
+            let _ = release_dock_space(window_handle);
           }
         })
         .collect::<Vec<_>>()
@@ -657,24 +2161,48 @@ impl WidgetFactory {
       );
 
       let _ = self.stop_by_id(&widget_state.id);
+    }
 
-      self
-        .start_widget(&widget_state.config_path, &widget_state.open_options)
-        .await?;
+    // Reopen each widget once, rather than once per monitor. Monitors
+    // with a window that wasn't relaunched are skipped.
+    let mut reopened = vec![];
+
+    for widget_state in changed_states {
+      let widget_key = (widget_state.config_path, widget_state.open_options);
+
+      if reopened.contains(&widget_key) {
+        continue;
+      }
+
+      self.open_widget(&widget_key.0, &widget_key.1, true).await?;
+      reopened.push(widget_key);
     }
 
     Ok(())
   }
 
-  /// Relaunches widgets with the given config paths.
-  pub async fn relaunch_by_paths(&self, config_paths: &Vec<PathBuf>) -> anyhow::Result<()> {
+  /// Relaunches widgets that were opened with an outdated version of
+  /// the given configs.
+  ///
+  /// Widgets that already run with the changed config (e.g. after a
+  /// profile switch) are skipped.
+  pub async fn relaunch_by_configs(
+    &self,
+    changed_configs: &HashMap<PathBuf, WidgetConfig>,
+  ) -> anyhow::Result<()> {
     let widget_ids = {
       self
         .widget_states
         .lock()
         .await
         .iter()
-        .filter(|(_, state)| config_paths.contains(&state.config_path))
+        .filter(|(_, state)| {
+          changed_configs
+            .get(&state.config_path)
+            .is_some_and(|config| {
+              serde_json::to_value(config).ok() != serde_json::to_value(&state.config).ok()
+            })
+        })
         .map(|(id, _)| id.clone())
         .collect::<Vec<_>>()
     };
@@ -692,6 +2220,33 @@ impl WidgetFactory {
     self.widget_states.lock().await.get(widget_id).cloned()
   }
 
+  /// Returns the state of the widget that a Tauri window acts on behalf
+  /// of.
+  ///
+  /// This is the widget itself, except for the dropdown menu window,
+  /// which acts on behalf of the widget that last opened it.
+  pub async fn state_by_window_label(&self, label: &str) -> Option<WidgetState> {
+    #[cfg(target_os = "windows")]
+    let is_menu_window = label == MENU_WINDOW_LABEL;
+
+    #[cfg(not(target_os = "windows"))]
+    let is_menu_window = false;
+
+    match is_menu_window {
+      true => {
+        let owner_id = self.menu_owner_id.lock().await.clone()?;
+        self.state_by_id(&owner_id).await
+      }
+      false => self.state_by_id(label).await,
+    }
+  }
+
+  /// Sets the widget that the dropdown menu window acts on behalf of.
+  #[cfg(target_os = "windows")]
+  pub async fn set_menu_owner(&self, widget_id: &str) {
+    *self.menu_owner_id.lock().await = Some(widget_id.to_string());
+  }
+
   /// Returns widget states grouped by their config paths.
   pub async fn states_by_path(&self) -> HashMap<PathBuf, Vec<WidgetState>> {
     self
@@ -709,3 +2264,190 @@ impl WidgetFactory {
       })
   }
 }
+
+/// Gets the native handle of a window (i.e. the HWND on Windows and the
+/// X11 window ID on Linux).
+fn window_handle(window: &tauri::WebviewWindow) -> anyhow::Result<isize> {
+  #[cfg(target_os = "linux")]
+  {
+    Ok(window.as_ref().window().x11_window_id()? as isize)
+  }
+
+  #[cfg(target_os = "windows")]
+  {
+    Ok(window.hwnd().context("Failed to get window handle.")?.0 as isize)
+  }
+}
+
+/// Reserves screen space for a docked window, such that other windows
+/// are kept out of it. Returns the bounds of the allocated space.
+fn reserve_dock_space(
+  window: &tauri::WebviewWindow,
+  size: PhysicalSize<i32>,
+  position: PhysicalPosition<i32>,
+  edge: DockEdge,
+) -> anyhow::Result<(PhysicalSize<i32>, PhysicalPosition<i32>)> {
+  #[cfg(target_os = "linux")]
+  {
+    window
+      .as_ref()
+      .window()
+      .allocate_dock_strut(size, position, edge)
+  }
+
+  #[cfg(target_os = "windows")]
+  {
+    window
+      .as_ref()
+      .window()
+      .allocate_app_bar(size, position, edge)
+  }
+}
+
+/// Deallocates the reserved screen space of a docked window.
+fn release_dock_space(window_handle: isize) -> anyhow::Result<()> {
+  #[cfg(target_os = "linux")]
+  {
+    remove_dock_strut(window_handle as u32)
+  }
+
+  #[cfg(target_os = "windows")]
+  {
+    remove_app_bar(window_handle)
+  }
+}
+
+/// Derives a deterministic widget ID from the config path, the way the
+/// widget was opened, and the monitor's device ID.
+///
+/// Example: `widget-starter_vanilla-default-DP-1`.
+fn derive_widget_id(
+  formatted_path: &str,
+  open_options: &WidgetOpenOptions,
+  device_id: &str,
+) -> String {
+  let open_options_id = match open_options {
+    WidgetOpenOptions::Preset(name) => name.clone(),
+    // Standalone placements are identified by a hash of the placement.
+    WidgetOpenOptions::Standalone(placement) => {
+      let placement_json = serde_json::to_string(placement).unwrap_or_default();
+      format!("standalone_{:08x}", content_hash(&placement_json) as u32)
+    }
+  };
+
+  format!(
+    "widget-{}-{}-{}",
+    sanitize_id(formatted_path),
+    sanitize_id(&open_options_id),
+    device_id
+  )
+}
+
+/// Replaces characters that are not allowed in Tauri window labels.
+fn sanitize_id(id: &str) -> String {
+  let is_allowed = |char: char| char.is_ascii_alphanumeric() || matches!(char, '-' | '_');
+
+  id.chars()
+    .map(|char| if is_allowed(char) { char } else { '_' })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::monitor_state::WorkArea;
+
+  fn monitor() -> Monitor {
+    Monitor {
+      name: Some("DP-1".to_string()),
+      is_primary: true,
+      x: 0,
+      y: 0,
+      width: 1000,
+      height: 800,
+      scale_factor: 1.,
+      manufacturer: None,
+      model: None,
+      serial: None,
+      work_area: WorkArea {
+        x: 0,
+        y: 0,
+        width: 1000,
+        height: 800,
+      },
+    }
+  }
+
+  fn placement() -> WidgetPlacement {
+    serde_json::from_value(serde_json::json!({
+      "anchor": "top_left",
+      "offsetX": "0px",
+      "offsetY": "0px",
+      "width": "100px",
+      "height": "50px",
+      "monitorSelection": { "type": "all" },
+    }))
+    .unwrap()
+  }
+
+  #[test]
+  fn test_user_placement_skips_unchanged_bounds() {
+    let user_placement = WidgetFactory::user_placement_for_bounds(
+      placement(),
+      None,
+      &monitor(),
+      PhysicalPosition::new(0, 0),
+      PhysicalSize::new(100, 50),
+      true,
+    );
+
+    assert_eq!(user_placement, None);
+  }
+
+  #[test]
+  fn test_user_placement_keeps_size_on_move_after_resize() {
+    let resized = WidgetFactory::user_placement_for_bounds(
+      placement(),
+      None,
+      &monitor(),
+      PhysicalPosition::new(0, 0),
+      PhysicalSize::new(200, 80),
+      true,
+    )
+    .unwrap();
+
+    assert_eq!(resized.width, Some(LengthValue::from_ppx(200.)));
+    assert_eq!(resized.height, Some(LengthValue::from_ppx(80.)));
+
+    let moved = WidgetFactory::user_placement_for_bounds(
+      placement(),
+      Some(&resized),
+      &monitor(),
+      PhysicalPosition::new(30, 40),
+      PhysicalSize::new(200, 80),
+      true,
+    )
+    .unwrap();
+
+    assert_eq!(moved.offset_x, LengthValue::from_ppx(30.));
+    assert_eq!(moved.offset_y, LengthValue::from_ppx(40.));
+    assert_eq!(moved.width, Some(LengthValue::from_ppx(200.)));
+    assert_eq!(moved.height, Some(LengthValue::from_ppx(80.)));
+  }
+
+  #[test]
+  fn test_user_placement_ignores_size_when_not_resizable() {
+    let user_placement = WidgetFactory::user_placement_for_bounds(
+      placement(),
+      None,
+      &monitor(),
+      PhysicalPosition::new(30, 40),
+      PhysicalSize::new(200, 80),
+      false,
+    )
+    .unwrap();
+
+    assert_eq!(user_placement.width, None);
+    assert_eq!(user_placement.height, None);
+  }
+}